@@ -0,0 +1,14 @@
+//! Cargo doesn't know that `import_solutions!` depends on the contents of `src/puzzle`, so
+//! list them here. A changed listing re-runs the macro and picks up new solutions.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/puzzle");
+
+    let mut files: Vec<String> = std::fs::read_dir("src/puzzle")
+        .expect("src/puzzle should exist")
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    files.sort();
+    println!("cargo:rustc-env=ADVENT_PUZZLE_FILES={}", files.join(","));
+}
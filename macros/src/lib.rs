@@ -4,61 +4,661 @@ use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use std::fs::read_dir;
+use std::path::Path;
 
 /// Looks in the puzzle director for all files matching `day_*.rs` and imports them as modules.
+/// Also builds `SOLUTIONS`, the registry of every imported solution, ordered by day.
+///
+/// Problems with the files, like a malformed name or a missing `DayXX` type, are reported as
+/// compile errors. The crate's build script lists the directory in `ADVENT_PUZZLE_FILES`, which
+/// is read here so that adding a file re-runs this macro.
 #[proc_macro]
 pub fn import_solutions(_item: TokenStream) -> TokenStream {
-    let imports = TokenStream2::from_iter(get_days().into_iter().map(|day| {
+    let (days, errors) = match get_days() {
+        Ok(days) => days,
+        Err(e) => return compile_error(&e).into(),
+    };
+    let errors = TokenStream2::from_iter(errors.iter().map(|e| compile_error(e)));
+
+    let imports = TokenStream2::from_iter(days.iter().map(|day| {
         let module_name = format_ident!("day_{:02}", day);
         let type_name = format_ident!("Day{:02}", day);
-        proc_macro2::TokenStream::from(quote! {
+        quote! {
             mod #module_name;
             pub use #module_name::#type_name;
-        })
+        }
     }));
 
-    imports.into()
+    let entries = TokenStream2::from_iter(days.iter().map(|day| {
+        let type_name = format_ident!("Day{:02}", day);
+        quote! {
+            Solution { info: #type_name::INFO, new: <#type_name as Puzzle>::new },
+        }
+    }));
+
+    quote! {
+        #errors
+        #imports
+
+        const _: Option<&str> = option_env!("ADVENT_PUZZLE_FILES");
+
+        /// Every solution in `src/puzzle`, ordered by day
+        pub static SOLUTIONS: &[Solution] = &[#entries];
+    }
+    .into()
 }
 
-#[proc_macro]
-pub fn get_solution(item: TokenStream) -> TokenStream {
-    let item = TokenStream2::from(item);
+/// Describes a solution: `#[puzzle(day = 5, title = "...", tags = ["grid"])]`.
+/// `parts` lists the parts that are implemented, and defaults to `[1, 2]`.
+///
+/// Generates `INFO`, a `PuzzleInfo` constant on the annotated type, which is
+/// what `import_solutions!` puts in the registry.
+#[proc_macro_attribute]
+pub fn puzzle(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemStruct);
+    let mut day: Option<syn::LitInt> = None;
+    let mut title: Option<syn::LitStr> = None;
+    let mut tags: Vec<syn::LitStr> = vec![];
+    let mut parts: Option<Vec<syn::LitInt>> = None;
 
-    let solutions = TokenStream2::from_iter(get_days().into_iter().map(|day| {
-        let type_name = format_ident!("Day{:02}", day);
-        proc_macro2::TokenStream::from(quote! {
-            #day => Box::new(#type_name::new(#item)),
-        })
-    }));
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("day") {
+            day = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("title") {
+            title = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("tags") {
+            tags = parse_list(meta.value()?)?;
+        } else if meta.path.is_ident("parts") {
+            parts = Some(parse_list(meta.value()?)?);
+        } else {
+            return Err(meta.error("expected `day`, `title`, `tags` or `parts`"));
+        }
+        Ok(())
+    });
+    syn::parse_macro_input!(attr with parser);
+
+    let span = proc_macro2::Span::call_site();
+    let Some(day) = day else {
+        return syn::Error::new(span, "missing `day = N`")
+            .to_compile_error()
+            .into();
+    };
+    let Some(title) = title else {
+        return syn::Error::new(span, "missing `title = \"...\"`")
+            .to_compile_error()
+            .into();
+    };
+    let day_number = match day.base10_parse::<u8>() {
+        Ok(day_number) => day_number,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let expected = format_ident!("Day{:02}", day_number);
+    if item.ident != expected {
+        return syn::Error::new(
+            day.span(),
+            format!("day {day_number} should be implemented by `{expected}`"),
+        )
+        .to_compile_error()
+        .into();
+    }
+    let parts = match parts {
+        Some(parts) => quote! { #(#parts),* },
+        None => quote! { 1, 2 },
+    };
 
+    let ident = &item.ident;
     quote! {
-        match #item.day as u32 {
-            #solutions
-            _ => anyhow::bail!("No solution found for day {} (src/puzzle/day_{:02}.rs)", #item.day, #item.day),
+        #item
+
+        impl #ident {
+            pub const INFO: crate::PuzzleInfo = crate::PuzzleInfo {
+                day: #day,
+                title: #title,
+                tags: &[#(#tags),*],
+                parts: &[#parts],
+            };
         }
     }
     .into()
 }
 
-fn get_days() -> Vec<u32> {
-    read_dir("./src/puzzle")
-        .unwrap()
-        .filter_map(|entry| {
-            let entry = entry.unwrap();
-            let filename = entry.file_name().into_string().unwrap();
-            let Ok((_, day)) = parse_day_from_file(&filename) else {
-                return None;
-            };
-            Some(day)
-        })
-        .filter(|d| *d > 0)
-        .collect()
+/// Parse `[a, b, c]`
+fn parse_list<T: syn::parse::Parse>(input: syn::parse::ParseStream) -> syn::Result<Vec<T>> {
+    let content;
+    syn::bracketed!(content in input);
+    let items = content.parse_terminated(T::parse, syn::Token![,])?;
+    Ok(items.into_iter().collect())
+}
+
+fn compile_error(message: &str) -> TokenStream2 {
+    quote! { compile_error!(#message); }
+}
+
+/// Days with a solution in `src/puzzle`, in order, along with any problems found in that
+/// directory. Fails if the directory can't be read at all.
+fn get_days() -> Result<(Vec<u32>, Vec<String>), String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "CARGO_MANIFEST_DIR is not set; build with cargo".to_string())?;
+    let dir = Path::new(&manifest_dir).join("src/puzzle");
+    let entries = read_dir(&dir).map_err(|e| format!("can't read {}: {e}", dir.display()))?;
+
+    let mut days = vec![];
+    let mut errors = vec![];
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("can't read {}: {e}", dir.display()))?;
+        let Ok(filename) = entry.file_name().into_string() else {
+            continue;
+        };
+        if !filename.starts_with("day_") || !filename.ends_with(".rs") {
+            continue;
+        }
+        let day = match parse_day_from_file(&filename) {
+            Ok(("", day)) if day <= 25 => day,
+            _ => {
+                errors.push(format!(
+                    "src/puzzle/{filename}: expected a file name like `day_05.rs`, for days 1 to 25"
+                ));
+                continue;
+            }
+        };
+        // The template is imported by hand
+        if day == 0 {
+            continue;
+        }
+        match check_solution_file(&entry.path(), day) {
+            Ok(()) => days.push(day),
+            Err(e) => errors.push(format!("src/puzzle/{filename}: {e}")),
+        }
+    }
+
+    days.sort();
+    Ok((days, errors))
+}
+
+/// Make sure the file defines the type the registry will refer to. Files that don't parse are
+/// left to the compiler, which gives a better error than we can.
+fn check_solution_file(path: &Path, day: u32) -> Result<(), String> {
+    let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let Ok(file) = syn::parse_file(&source) else {
+        return Ok(());
+    };
+
+    let type_name = format_ident!("Day{:02}", day);
+    let item = file.items.iter().find_map(|item| match item {
+        syn::Item::Struct(item) if item.ident == type_name => Some(item),
+        _ => None,
+    });
+    let Some(item) = item else {
+        return Err(format!("missing `pub struct {type_name}`"));
+    };
+    if !item.attrs.iter().any(|attr| attr.path().is_ident("puzzle")) {
+        return Err(format!(
+            "`{type_name}` needs a `#[puzzle(day = {day}, title = \"...\")]` attribute"
+        ));
+    }
+    Ok(())
 }
 
 fn parse_day_from_file(filename: &str) -> IResult<&str, u32> {
     let (input, _) = nom::bytes::complete::tag("day_")(filename)?;
-    let (input, day) = nom::character::complete::digit1(input)?;
+    let (input, day) =
+        nom::bytes::complete::take_while_m_n(2, 2, |c: char| c.is_ascii_digit())(input)?;
+    let (input, _) = nom::bytes::complete::tag(".rs")(input)?;
     let day: u32 = day.parse().unwrap();
 
     Ok((input, day))
 }
+
+/// Implements `GridTile`, `TryFrom<char>`, `FromStr` and `Display` for a fieldless enum.
+/// Each variant needs `#[tile('c')]`, or `#[tile('c', render = 'r')]` to display it as a
+/// different character. `#[tile(debug)]` on the enum implements `Debug` as well.
+#[proc_macro_derive(GridTile, attributes(tile))]
+pub fn derive_grid_tile(item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::DeriveInput);
+    match grid_tile(&item) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn grid_tile(item: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let syn::Data::Enum(data) = &item.data else {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "GridTile can only be derived for enums",
+        ));
+    };
+
+    let mut debug = false;
+    for attr in item.attrs.iter().filter(|a| a.path().is_ident("tile")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("debug") {
+                debug = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `debug`"))
+            }
+        })?;
+    }
+
+    let mut variants = vec![];
+    let mut parsed = vec![];
+    let mut rendered = vec![];
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "GridTile variants can't have fields",
+            ));
+        }
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|a| a.path().is_ident("tile"))
+            .ok_or_else(|| syn::Error::new_spanned(variant, "missing `#[tile('c')]`"))?;
+        let (c, render) = attr.parse_args_with(|input: syn::parse::ParseStream| {
+            let c: syn::LitChar = input.parse()?;
+            let mut render = None;
+            if input.parse::<Option<syn::Token![,]>>()?.is_some() {
+                let name: syn::Ident = input.parse()?;
+                if name != "render" {
+                    return Err(syn::Error::new_spanned(name, "expected `render = 'c'`"));
+                }
+                input.parse::<syn::Token![=]>()?;
+                render = Some(input.parse::<syn::LitChar>()?);
+            }
+            Ok((c.clone(), render.unwrap_or(c)))
+        })?;
+        if let Some(idx) = parsed
+            .iter()
+            .position(|p: &syn::LitChar| p.value() == c.value())
+        {
+            let other: &syn::Ident = variants[idx];
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("{:?} is already used by `{other}`", c.value()),
+            ));
+        }
+        variants.push(&variant.ident);
+        parsed.push(c);
+        rendered.push(render);
+    }
+
+    let ident = &item.ident;
+    let debug = debug.then(|| {
+        quote! {
+            impl std::fmt::Debug for #ident {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self, f)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl crate::GridTile for #ident {
+            fn from_char(c: char) -> Option<Self> {
+                match c {
+                    #(#parsed => Some(Self::#variants),)*
+                    _ => None,
+                }
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    #(Self::#variants => #rendered,)*
+                }
+            }
+        }
+
+        impl TryFrom<char> for #ident {
+            type Error = crate::TileError;
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                <Self as crate::GridTile>::from_char(c).ok_or_else(|| crate::TileError::new(c))
+            }
+        }
+
+        impl std::str::FromStr for #ident {
+            type Err = crate::TileError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Self::try_from(c),
+                    _ => Err(crate::TileError::new(s)),
+                }
+            }
+        }
+
+        impl std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                use std::fmt::Write;
+                f.write_char(<Self as crate::GridTile>::to_char(self))
+            }
+        }
+
+        #debug
+    })
+}
+
+/// Implements `Parse` and `FromStr` from a `#[format("...")]` template. On a struct the
+/// attribute goes on the struct, and on an enum it goes on each variant. See `crate::parse`.
+#[proc_macro_derive(Format, attributes(format))]
+pub fn derive_format(item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::DeriveInput);
+    match format(&item) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+enum Segment {
+    Literal(String),
+    Field(syn::Ident, Option<String>),
+}
+
+fn format(item: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &item.ident;
+    let body = match &item.data {
+        syn::Data::Struct(data) => {
+            let template = format_attr(&item.attrs, ident)?;
+            format_parser(&template, &data.fields, quote! { Self })?
+        }
+        syn::Data::Enum(data) => {
+            let mut variants = vec![];
+            let mut templates = vec![];
+            for variant in &data.variants {
+                let template = format_attr(&variant.attrs, &variant.ident)?;
+                let name = &variant.ident;
+                let parser = format_parser(&template, &variant.fields, quote! { Self::#name })?;
+                variants.push(quote! {
+                    let variant = |input| -> crate::parse::PResult<'_, Self> { #parser };
+                    if let Ok(result) = variant(input) {
+                        return Ok(result);
+                    }
+                });
+                templates.push(format!("{:?}", template.value()));
+            }
+            let expected = match templates.split_last() {
+                Some((last, [])) => format!("expected {last}"),
+                Some((last, rest)) => format!("expected {} or {last}", rest.join(", ")),
+                None => "expected nothing".to_string(),
+            };
+            quote! {
+                #(#variants)*
+                crate::parse::expected(input, #expected)
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "Format can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl crate::parse::Parse for #ident {
+            fn parser(input: &str) -> crate::parse::PResult<'_, Self> {
+                #body
+            }
+        }
+
+        impl std::str::FromStr for #ident {
+            type Err = crate::parse::ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                crate::parse::parse_line(s, 1)
+            }
+        }
+    })
+}
+
+fn format_attr(attrs: &[syn::Attribute], ident: &syn::Ident) -> syn::Result<syn::LitStr> {
+    attrs
+        .iter()
+        .find(|a| a.path().is_ident("format"))
+        .ok_or_else(|| syn::Error::new_spanned(ident, "missing `#[format(\"...\")]`"))?
+        .parse_args()
+}
+
+/// Statements that parse `fields` as laid out in `template`, and return `construct { fields }`
+fn format_parser(
+    template: &syn::LitStr,
+    fields: &syn::Fields,
+    construct: TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let names = match fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .filter_map(|f| f.ident.clone())
+            .collect(),
+        syn::Fields::Unit => vec![],
+        syn::Fields::Unnamed(_) => {
+            return Err(syn::Error::new_spanned(fields, "Format needs named fields"))
+        }
+    };
+
+    let segments = parse_template(template)?;
+    let mut used = vec![];
+    let mut steps = vec![];
+    for segment in segments {
+        match segment {
+            Segment::Literal(text) => {
+                let expected = format!("expected {text:?}");
+                steps.push(quote! {
+                    let (input, _) = crate::parse::literal(#text, #expected)(input)?;
+                });
+            }
+            Segment::Field(name, sep) => {
+                if !names.contains(&name) {
+                    return Err(syn::Error::new_spanned(
+                        template,
+                        format!("no field named `{name}`"),
+                    ));
+                }
+                if used.contains(&name) {
+                    return Err(syn::Error::new_spanned(
+                        template,
+                        format!("`{name}` is used more than once"),
+                    ));
+                }
+                let expected = format!("expected `{name}`");
+                let parser = match sep {
+                    Some(sep) => quote! { crate::parse::list(#sep) },
+                    None => quote! { crate::parse::Parse::parser },
+                };
+                steps.push(quote! {
+                    let (input, #name) = crate::parse::field(#expected, #parser)(input)?;
+                });
+                used.push(name);
+            }
+        }
+    }
+    if let Some(missing) = names.iter().find(|n| !used.contains(n)) {
+        return Err(syn::Error::new_spanned(
+            template,
+            format!("`{missing}` isn't in the format"),
+        ));
+    }
+
+    let construct = match fields {
+        syn::Fields::Unit => construct,
+        _ => quote! { #construct { #(#used),* } },
+    };
+    Ok(quote! {
+        #(#steps)*
+        Ok((input, #construct))
+    })
+}
+
+/// Split `"Game {num}: {rounds:; }"` into literals and fields
+fn parse_template(template: &syn::LitStr) -> syn::Result<Vec<Segment>> {
+    let text = template.value();
+    let error = |message: &str| syn::Error::new_spanned(template, message);
+
+    let mut segments = vec![];
+    let mut literal = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err(error("unmatched `}`; use `}}` for a literal brace")),
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => field.push(c),
+                        None => return Err(error("unterminated `{`")),
+                    }
+                }
+                let (name, sep) = match field.split_once(':') {
+                    Some((name, sep)) if !sep.is_empty() => (name, Some(sep.to_string())),
+                    Some(_) => return Err(error("empty list separator")),
+                    None => (field.as_str(), None),
+                };
+                let name = syn::parse_str::<syn::Ident>(name.trim())
+                    .map_err(|_| error(&format!("`{name}` isn't a field name")))?;
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Field(name, sep));
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+/// Caches the results of a function, keyed on its arguments. See `crate::memo`.
+/// `#[memoize(key = (a.len(), b), key_type = (usize, u32))]` picks a different key.
+#[proc_macro_attribute]
+pub fn memoize(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemFn);
+    let mut key: Option<syn::Expr> = None;
+    let mut key_type: Option<syn::Type> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("key") {
+            key = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("key_type") {
+            key_type = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `key` or `key_type`"));
+        }
+        Ok(())
+    });
+    syn::parse_macro_input!(attr with parser);
+
+    match memoized(item, key, key_type) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn memoized(
+    item: syn::ItemFn,
+    key: Option<syn::Expr>,
+    key_type: Option<syn::Type>,
+) -> syn::Result<TokenStream2> {
+    let sig = &item.sig;
+    let syn::ReturnType::Type(_, value_type) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "memoized functions need to return something",
+        ));
+    };
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "memoized functions can't be generic",
+        ));
+    }
+
+    let (key, key_type) = match (key, key_type) {
+        (Some(key), Some(key_type)) => (quote! { #key }, quote! { #key_type }),
+        (None, None) => default_key(sig)?,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "`key` and `key_type` go together",
+            ))
+        }
+    };
+
+    let syn::ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = &item;
+    let name = sig.ident.to_string();
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            thread_local! {
+                static CACHE: std::cell::RefCell<crate::memo::Cache<#key_type, #value_type>> =
+                    std::cell::RefCell::new(crate::memo::Cache::new(#name));
+            }
+
+            let key: #key_type = #key;
+            if let Some(value) = CACHE.with(|cache| cache.borrow_mut().get(&key)) {
+                return value;
+            }
+            let _call = crate::memo::Cache::enter(&CACHE);
+            #[allow(clippy::redundant_closure_call)]
+            let value = (|| -> #value_type #block)();
+            CACHE.with(|cache| cache.borrow_mut().insert(key, value.clone()));
+            value
+        }
+    })
+}
+
+/// An owned copy of every argument, and its type
+fn default_key(sig: &syn::Signature) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let mut exprs = vec![];
+    let mut types = vec![];
+    for input in &sig.inputs {
+        let syn::FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new_spanned(
+                input,
+                "memoizing a method needs an explicit `key` and `key_type`",
+            ));
+        };
+        let syn::Pat::Ident(pat) = &*arg.pat else {
+            return Err(syn::Error::new_spanned(
+                &arg.pat,
+                "destructured arguments need an explicit `key` and `key_type`",
+            ));
+        };
+        let name = &pat.ident;
+        let (expr, ty) = match &*arg.ty {
+            syn::Type::Reference(r) => match &*r.elem {
+                syn::Type::Slice(slice) => {
+                    let elem = &slice.elem;
+                    (quote! { #name.to_vec() }, quote! { Vec<#elem> })
+                }
+                syn::Type::Path(p) if p.path.is_ident("str") => {
+                    (quote! { #name.to_string() }, quote! { String })
+                }
+                elem => (quote! { (*#name).clone() }, quote! { #elem }),
+            },
+            ty => (quote! { #name.clone() }, quote! { #ty }),
+        };
+        exprs.push(expr);
+        types.push(ty);
+    }
+    Ok((quote! { (#(#exprs,)*) }, quote! { (#(#types,)*) }))
+}
@@ -0,0 +1,316 @@
+use crate::{client, Config, RootOpt};
+use clap::Parser;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Environment variable checked by the AoC client for a session cookie
+pub const SESSION_COOKIE_ENV_VAR: &str = "ADVENT_OF_CODE_SESSION";
+const HIDDEN_SESSION_COOKIE_FILE: &str = ".adventofcode.session";
+const SESSION_COOKIE_FILE: &str = "adventofcode.session";
+
+#[derive(Parser, Debug, Clone)]
+pub struct AuthCommand {
+    /// Don't talk to adventofcode.com. Any well formed cookie is accepted.
+    #[arg(long, hide = true)]
+    fake_backend: bool,
+
+    #[command(subcommand)]
+    action: AuthAction,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+enum AuthAction {
+    /// Store a session cookie. Reads from stdin if no cookie is given.
+    Set { cookie: Option<String> },
+    /// Show which session cookie is in use, and where it came from
+    Show,
+    /// Check that the session cookie is accepted by adventofcode.com
+    Check,
+}
+
+/// Where a session cookie was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CookieSource {
+    Env,
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub cookie: String,
+    pub source: CookieSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    Valid,
+    /// The server didn't accept the cookie. Most likely it has expired.
+    Rejected,
+}
+
+/// Something that can tell us whether a session cookie is any good
+pub trait Backend {
+    fn check_session(&self, cookie: &str) -> Result<SessionStatus, anyhow::Error>;
+}
+
+/// Asks adventofcode.com
+pub struct HttpBackend;
+
+/// Accepts a fixed set of cookies, or any well formed cookie if the set is empty.
+/// Used for testing without network access.
+#[derive(Default)]
+pub struct FakeBackend {
+    pub accepted: Vec<String>,
+}
+
+impl Session {
+    /// Find the session cookie. A configured `session_file` wins, then the
+    /// environment, then the AoC client's default locations.
+    pub fn discover(config: &Config) -> Result<Self, anyhow::Error> {
+        if let Some(file) = &config.session_file {
+            return Self::from_file(file);
+        }
+
+        if let Ok(cookie) = std::env::var(SESSION_COOKIE_ENV_VAR) {
+            if !cookie.trim().is_empty() {
+                return Self::new(&cookie, CookieSource::Env);
+            }
+            log::warn!("{SESSION_COOKIE_ENV_VAR} is set but empty, ignoring");
+        }
+
+        match Self::default_locations().into_iter().find(|p| p.exists()) {
+            Some(file) => Self::from_file(&file),
+            None => anyhow::bail!(
+                "no session cookie found. Run `auth set`, set {SESSION_COOKIE_ENV_VAR}, \
+                 or add `session_file` to advent.toml"
+            ),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, anyhow::Error> {
+        let cookie = std::fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!("failed to read session cookie {}: {e}", path.display())
+        })?;
+        Self::new(&cookie, CookieSource::File(path.to_path_buf()))
+    }
+
+    pub fn new(cookie: &str, source: CookieSource) -> Result<Self, anyhow::Error> {
+        let cookie = cookie.trim();
+        if cookie.is_empty() || !cookie.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!("malformed session cookie from {source}: expected a hex string");
+        }
+        Ok(Self {
+            cookie: cookie.to_string(),
+            source,
+        })
+    }
+
+    /// Where `auth set` writes the cookie: the configured file, if there is one,
+    /// otherwise the first of the default locations.
+    pub fn store_path(config: &Config) -> Result<PathBuf, anyhow::Error> {
+        if let Some(file) = &config.session_file {
+            return Ok(file.clone());
+        }
+        Self::default_locations()
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no home directory; set `session_file` in advent.toml"))
+    }
+
+    fn default_locations() -> Vec<PathBuf> {
+        [
+            dirs::home_dir().map(|d| d.join(HIDDEN_SESSION_COOKIE_FILE)),
+            dirs::config_dir().map(|d| d.join(SESSION_COOKIE_FILE)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Write the cookie to `path`, readable only by the current user
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        use std::io::Write;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // mode() only applies to newly created files
+            if path.exists() {
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        let mut file = options.open(path)?;
+        writeln!(file, "{}", self.cookie)?;
+        Ok(())
+    }
+
+    /// The cookie with most of it hidden, for display
+    pub fn masked(&self) -> String {
+        let len = self.cookie.len();
+        if len <= 8 {
+            return "*".repeat(len);
+        }
+        format!("{}…{}", &self.cookie[..4], &self.cookie[len - 4..])
+    }
+}
+
+impl std::fmt::Display for CookieSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Env => write!(f, "${SESSION_COOKIE_ENV_VAR}"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl Backend for HttpBackend {
+    fn check_session(&self, cookie: &str) -> Result<SessionStatus, anyhow::Error> {
+        // The settings page is only served to logged in users. Everyone else gets
+        // redirected.
+        let response = client::http_client(cookie)?
+            .get("https://adventofcode.com/settings")
+            .send()?;
+        let status = response.status();
+        log::debug!("session check: {status}");
+        if status.is_success() {
+            return Ok(SessionStatus::Valid);
+        }
+        let to_login = status.is_redirection()
+            && response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .is_some_and(|location| location.contains("login"));
+        if to_login
+            || status == reqwest::StatusCode::UNAUTHORIZED
+            || status == reqwest::StatusCode::FORBIDDEN
+        {
+            Ok(SessionStatus::Rejected)
+        } else {
+            anyhow::bail!("unexpected response checking the session cookie: {status}")
+        }
+    }
+}
+
+impl Backend for FakeBackend {
+    fn check_session(&self, cookie: &str) -> Result<SessionStatus, anyhow::Error> {
+        if self.accepted.is_empty() || self.accepted.iter().any(|c| c == cookie) {
+            Ok(SessionStatus::Valid)
+        } else {
+            Ok(SessionStatus::Rejected)
+        }
+    }
+}
+
+impl AuthCommand {
+    pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
+        log::info!("Running auth command");
+
+        let backend: Box<dyn Backend> = if self.fake_backend {
+            Box::<FakeBackend>::default()
+        } else {
+            Box::new(HttpBackend)
+        };
+
+        match &self.action {
+            AuthAction::Set { cookie } => {
+                let cookie = match cookie {
+                    Some(cookie) => cookie.clone(),
+                    None => {
+                        let mut cookie = String::new();
+                        std::io::stdin().read_to_string(&mut cookie)?;
+                        cookie
+                    }
+                };
+                let path = Session::store_path(&opt.config)?;
+                let session = Session::new(&cookie, CookieSource::File(path.clone()))?;
+                match backend.check_session(&session.cookie) {
+                    Ok(SessionStatus::Valid) => {}
+                    Ok(SessionStatus::Rejected) => {
+                        log::warn!("adventofcode.com did not accept this cookie, saving it anyway")
+                    }
+                    Err(e) => log::warn!("couldn't check this cookie ({e}), saving it anyway"),
+                }
+                session.save(&path)?;
+                println!("Saved session cookie to {}", path.display());
+            }
+            AuthAction::Show => {
+                let session = Session::discover(&opt.config)?;
+                println!("Source: {}", session.source);
+                println!("Cookie: {}", session.masked());
+            }
+            AuthAction::Check => {
+                let session = Session::discover(&opt.config)?;
+                match backend.check_session(&session.cookie)? {
+                    SessionStatus::Valid => {
+                        println!("Session cookie from {} is valid", session.source)
+                    }
+                    SessionStatus::Rejected => anyhow::bail!(
+                        "session cookie from {} was rejected. It has probably expired; \
+                         log in again and run `auth set`",
+                        session.source
+                    ),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_session_file() {
+        let dir = std::env::temp_dir().join(format!("advent2023-auth-{}", std::process::id()));
+        let path = dir.join("cookie");
+        let config = Config {
+            session_file: Some(path.clone()),
+            ..Config::default()
+        };
+
+        let session = Session::new("0123456789abcdef", CookieSource::File(path.clone())).unwrap();
+        session
+            .save(&Session::store_path(&config).unwrap())
+            .unwrap();
+
+        let found = Session::discover(&config).unwrap();
+        assert_eq!(found.cookie, "0123456789abcdef");
+        assert_eq!(found.source, CookieSource::File(path.clone()));
+        assert_eq!(found.masked(), "0123…cdef");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fake_backend() {
+        assert!(Session::new("not a cookie", CookieSource::Env).is_err());
+
+        let backend = FakeBackend {
+            accepted: vec!["abc123".to_string()],
+        };
+        assert_eq!(
+            backend.check_session("abc123").unwrap(),
+            SessionStatus::Valid
+        );
+        assert_eq!(
+            backend.check_session("def456").unwrap(),
+            SessionStatus::Rejected
+        );
+    }
+}
@@ -1,14 +1,22 @@
+use crate::auth::Session;
+use crate::history::{self, Entry};
 use crate::RootOpt;
-use aoc_client::AocClient;
+use aoc_client::{AocClient, SubmissionOutcome};
 use clap::Parser;
 use std::path::PathBuf;
 
 pub struct Client {
-    pub client: AocClient,
-    pub assignment_path: PathBuf,
-    pub input_path: PathBuf,
-    pub year: i32,
-    pub day: u32,
+    pub session: Session,
+    pub year: u16,
+    /// The puzzle picked with `--day`, if there is one
+    puzzle: Option<PuzzleClient>,
+}
+
+/// What's needed to fetch and answer one day's puzzle
+struct PuzzleClient {
+    client: AocClient,
+    assignment_path: PathBuf,
+    input_path: PathBuf,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -25,60 +33,133 @@ pub struct SubmitCommand {
 
 impl Client {
     pub fn new(opt: &RootOpt) -> Result<Self, anyhow::Error> {
-        let assignment_path = PathBuf::from(format!("./assignments/day{:02}.md", opt.day));
-        let input_path = PathBuf::from(format!("./input/day{:02}.txt", opt.day));
-
-        let client = AocClient::builder()
-            .session_cookie_from_default_locations()?
-            .year(opt.year as i32)?
-            .day(opt.day as u32)?
-            .puzzle_filename(&assignment_path)
-            .build()?;
+        let config = &opt.config;
+        let session = Session::discover(config)?;
+        log::debug!("Using session cookie from {}", session.source);
+
+        let puzzle = match opt.day {
+            Some(day) => {
+                let assignment_path = config.assignment_path(day);
+                let input_path = config.input_path(day);
+                let client = AocClient::builder()
+                    .session_cookie(&session.cookie)?
+                    .year(config.year as i32)?
+                    .day(day as u32)?
+                    .puzzle_filename(&assignment_path)
+                    .build()?;
+                Some(PuzzleClient {
+                    client,
+                    assignment_path,
+                    input_path,
+                })
+            }
+            None => None,
+        };
 
         Ok(Self {
-            client,
-            assignment_path,
-            input_path,
-            year: opt.year as i32,
-            day: opt.day as u32,
+            session,
+            year: config.year,
+            puzzle,
         })
     }
 
+    fn puzzle(&self) -> Result<&PuzzleClient, anyhow::Error> {
+        self.puzzle
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no day specified (use --day)"))
+    }
+
     pub fn download(&self) -> Result<(), anyhow::Error> {
-        if !self.assignment_path.exists() {
-            self.client.save_puzzle_markdown()?;
+        let puzzle = self.puzzle()?;
+        if !puzzle.assignment_path.exists() {
+            if let Some(dir) = puzzle.assignment_path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            puzzle.client.save_puzzle_markdown()?;
         }
 
-        if !self.input_path.exists() {
-            let input = self.client.get_input()?;
-            std::fs::write(format!("./input/day{:02}.txt", self.day), input)?;
+        if !puzzle.input_path.exists() {
+            let input = puzzle.client.get_input().map_err(|e| {
+                anyhow::anyhow!(
+                    "failed to download input from {}: {e}. \
+                     If the session cookie has expired, run `auth check`",
+                    self.session.source
+                )
+            })?;
+            crate::input::save(&puzzle.input_path, &input)?;
         }
         Ok(())
     }
 
     /// Delete downloaded files
     pub fn clear(&self) -> Result<(), anyhow::Error> {
-        if self.assignment_path.exists() {
-            std::fs::remove_file(&self.assignment_path)?;
+        let puzzle = self.puzzle()?;
+        if puzzle.assignment_path.exists() {
+            std::fs::remove_file(&puzzle.assignment_path)?;
         }
 
-        if self.input_path.exists() {
-            std::fs::remove_file(&self.input_path)?;
+        if puzzle.input_path.exists() {
+            std::fs::remove_file(&puzzle.input_path)?;
         }
         Ok(())
     }
 
     /// Get the input for the day. If the input file doesn't exist, download it.
+    /// The input is normalized and checked; see [`crate::input`].
     pub fn get_input(&self) -> Result<String, anyhow::Error> {
-        if !self.input_path.exists() {
+        let puzzle = self.puzzle()?;
+        if !puzzle.input_path.exists() {
             self.download()?;
         }
 
-        let input = std::fs::read_to_string(&self.input_path)?;
-        Ok(input)
+        crate::input::load(&puzzle.input_path)
+    }
+
+    pub fn submit_answer(
+        &self,
+        part: u8,
+        answer: impl std::fmt::Display,
+    ) -> Result<SubmissionOutcome, anyhow::Error> {
+        Ok(self.puzzle()?.client.submit_answer(part as i64, answer)?)
+    }
+
+    /// Download the JSON for a private leaderboard
+    pub fn get_private_leaderboard(&self, id: u32) -> Result<String, anyhow::Error> {
+        let url = format!(
+            "https://adventofcode.com/{}/leaderboard/private/view/{id}.json",
+            self.year
+        );
+        log::info!("Downloading {url}");
+        let response = http_client(&self.session.cookie)?.get(url).send()?;
+        if response.status().is_redirection() {
+            anyhow::bail!(
+                "leaderboard {id} does not exist, you are not a member, \
+                 or the session cookie from {} has expired",
+                self.session.source
+            );
+        }
+        Ok(response.error_for_status()?.text()?)
     }
 }
 
+/// An HTTP client that sends the session cookie with every request. Redirects
+/// aren't followed, since AoC answers unauthenticated requests with one.
+pub fn http_client(cookie: &str) -> Result<reqwest::blocking::Client, anyhow::Error> {
+    use reqwest::header::{HeaderMap, HeaderValue, COOKIE, USER_AGENT};
+
+    let mut headers = HeaderMap::new();
+    headers.insert(COOKIE, HeaderValue::from_str(&format!("session={cookie}"))?);
+    headers.insert(
+        USER_AGENT,
+        HeaderValue::from_static("github.com/dgrijalva/advent2023"),
+    );
+
+    Ok(reqwest::blocking::Client::builder()
+        .default_headers(headers)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?)
+}
+
 impl DownloadCommand {
     pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
         log::info!("Running download command");
@@ -97,8 +178,16 @@ impl SubmitCommand {
     pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
         log::info!("Running submit command");
         let client = Client::new(opt)?;
-        let res = client.client.submit_answer(opt.part as i64, self.answer)?;
+        let res = client.submit_answer(opt.part, self.answer)?;
         println!("{:?}", res);
+        let entry = Entry::submission(
+            opt.config.year,
+            opt.day()?,
+            opt.part,
+            &self.answer.to_string(),
+            (&res).into(),
+        );
+        history::record(&opt.config.history_file, &entry);
         Ok(())
     }
 }
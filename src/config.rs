@@ -0,0 +1,140 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Name of the project config file, looked up in the current directory
+pub const CONFIG_FILE: &str = "advent.toml";
+
+/// Project settings, loaded from `advent.toml`. Every field is optional in the
+/// file, and command line flags take precedence over anything set here.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Default year to run
+    pub year: u16,
+    /// Where puzzle inputs are stored
+    pub input_dir: PathBuf,
+    /// Where puzzle descriptions are stored
+    pub assignment_dir: PathBuf,
+    /// Where downloaded data, like leaderboards, is cached
+    pub cache_dir: PathBuf,
+    /// Record of runs and submissions, used by `stats`
+    pub history_file: PathBuf,
+    /// File containing the session cookie. If unset, the AoC client's default
+    /// locations are used.
+    pub session_file: Option<PathBuf>,
+    /// How results are printed
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: 2023,
+            input_dir: PathBuf::from("./input"),
+            assignment_dir: PathBuf::from("./assignments"),
+            cache_dir: PathBuf::from("./cache"),
+            history_file: PathBuf::from("./history.jsonl"),
+            session_file: None,
+            output: OutputFormat::Text,
+        }
+    }
+}
+
+impl Config {
+    /// Load the config from `path`, or from `./advent.toml` if no path is given.
+    /// A missing default file is not an error; a missing explicit one is.
+    pub fn load(path: Option<&Path>) -> Result<Self, anyhow::Error> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let path = PathBuf::from(CONFIG_FILE);
+                if !path.exists() {
+                    log::debug!("No {CONFIG_FILE} found, using defaults");
+                    return Ok(Self::default());
+                }
+                path
+            }
+        };
+
+        log::debug!("Loading config from {}", path.display());
+        let data = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", path.display()))?;
+        let config = Self::parse(&data)
+            .map_err(|e| anyhow::anyhow!("invalid config {}: {e}", path.display()))?;
+
+        // Relative paths in the file are relative to the file, not to wherever we
+        // happen to be running from
+        let base = path.parent().unwrap_or(Path::new("."));
+        Ok(config.relative_to(base))
+    }
+
+    pub fn parse(data: &str) -> Result<Self, anyhow::Error> {
+        Ok(toml::from_str(data)?)
+    }
+
+    fn relative_to(mut self, base: &Path) -> Self {
+        let resolve = |p: &mut PathBuf| {
+            if p.is_relative() {
+                *p = base.join(&*p);
+            }
+        };
+        resolve(&mut self.input_dir);
+        resolve(&mut self.assignment_dir);
+        resolve(&mut self.cache_dir);
+        resolve(&mut self.history_file);
+        if let Some(p) = self.session_file.as_mut() {
+            resolve(p);
+        }
+        self
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.input_dir.join(format!("day{:02}.txt", day))
+    }
+
+    pub fn assignment_path(&self, day: u8) -> PathBuf {
+        self.assignment_dir.join(format!("day{:02}.md", day))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r#"
+            year = 2022
+            input_dir = "/data/aoc/input"
+            session_file = "cookie"
+            output = "json"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.year, 2022);
+        assert_eq!(config.input_dir, PathBuf::from("/data/aoc/input"));
+        assert_eq!(config.assignment_dir, PathBuf::from("./assignments"));
+        assert_eq!(config.output, OutputFormat::Json);
+
+        let config = config.relative_to(Path::new("/home/me/aoc"));
+        assert_eq!(config.input_dir, PathBuf::from("/data/aoc/input"));
+        assert_eq!(
+            config.session_file,
+            Some(PathBuf::from("/home/me/aoc/cookie"))
+        );
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        assert!(Config::parse("yaer = 2022").is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Finding where a simulation starts repeating itself, so the state after a
+//! huge number of steps can be worked out from the first few:
+//!
+//! ```ignore
+//! let grid = cycle::nth_state(grid, |g| g.spin(), 1_000_000_000);
+//! ```
+//!
+//! The hash based functions remember every state they've seen, and find the
+//! cycle as soon as it comes round the first time. `find_cycle_brent` and
+//! `nth_state_brent` only ever keep two states, but step through the cycle a
+//! few more times, and only need `PartialEq`.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// States `start..start + len` repeat forever after the first `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`
+    pub fn index_of(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+/// Step from `initial` until a state comes up again. Also returns every state
+/// up to the repeat, so any step's state can be looked up with `index_of`.
+pub fn find_cycle<S>(initial: S, mut step: impl FnMut(&S) -> S) -> (Cycle, Vec<S>)
+where
+    S: Hash + Eq + Clone,
+{
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let len = states.len() - start;
+            return (Cycle { start, len }, states);
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// The state after `n` steps from `initial`, skipping ahead once it cycles
+pub fn nth_state<S>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S
+where
+    S: Hash + Eq + Clone,
+{
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+    for i in 0..n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                len: i - start,
+            };
+            return states.swap_remove(cycle.index_of(n));
+        }
+        seen.insert(state.clone(), i);
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+    state
+}
+
+/// Brent's algorithm: like `find_cycle`, without keeping the states around
+pub fn find_cycle_brent<S>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle
+where
+    S: PartialEq + Clone,
+{
+    // Find the length by moving the tortoise up to the hare at every power
+    // of two, until the hare laps it
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    // With the hare a cycle ahead, they meet where the cycle starts
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, len }
+}
+
+/// Like `nth_state`, using `find_cycle_brent`
+pub fn nth_state_brent<S>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S
+where
+    S: PartialEq + Clone,
+{
+    let cycle = find_cycle_brent(&initial, &mut step);
+    let mut state = initial;
+    for _ in 0..cycle.index_of(n) {
+        state = step(&state);
+    }
+    state
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle() {
+        // 3 steps in, then round 10 -> 11 -> ... -> 14 -> 10
+        let step = |&x: &u32| if x < 14 { x + 1 } else { 10 };
+        let expected = Cycle { start: 3, len: 5 };
+
+        let (cycle, states) = find_cycle(7, step);
+        assert_eq!(cycle, expected);
+        assert_eq!(states[cycle.index_of(1_000)], 12);
+        assert_eq!(find_cycle_brent(&7, step), expected);
+
+        assert_eq!(nth_state(7, step, 2), 9);
+        assert_eq!(nth_state(7, step, 1_000), 12);
+        assert_eq!(nth_state_brent(7, step, 1_000), 12);
+        assert_eq!(nth_state(7, step, 8), nth_state_brent(7, step, 8));
+    }
+}
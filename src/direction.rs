@@ -59,3 +59,63 @@ impl std::ops::Neg for Direction {
         }
     }
 }
+
+/// A direction including the diagonals, for moving to any of the eight cells
+/// around a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// Clockwise, starting from north
+    pub fn all() -> [Self; 8] {
+        [
+            Self::North,
+            Self::NorthEast,
+            Self::East,
+            Self::SouthEast,
+            Self::South,
+            Self::SouthWest,
+            Self::West,
+            Self::NorthWest,
+        ]
+    }
+
+    /// How far a step moves in x and y, with y growing to the south
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Self::North => (0, -1),
+            Self::NorthEast => (1, -1),
+            Self::East => (1, 0),
+            Self::SouthEast => (1, 1),
+            Self::South => (0, 1),
+            Self::SouthWest => (-1, 1),
+            Self::West => (-1, 0),
+            Self::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let (x, y) = self.offset();
+        x != 0 && y != 0
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::North => Self::North,
+            Direction::East => Self::East,
+            Direction::South => Self::South,
+            Direction::West => Self::West,
+        }
+    }
+}
@@ -1,39 +1,141 @@
-use crate::{Direction, Pos};
+use crate::{Direction, Direction8, Pos};
 use itertools::Itertools;
-use std::{hash::Hash, str::FromStr};
+use std::{
+    hash::Hash,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
 
-pub struct Grid<T>(Vec<Vec<T>>);
+mod bits;
+mod distance;
+mod map;
+mod ray;
+mod region;
+mod search;
+mod sparse;
+mod tiled;
+mod view;
+
+pub use bits::BitGrid;
+pub use map::{Coord, GridMap};
+pub use region::{Connectivity, Region, Regions};
+pub use search::{Route, SearchState};
+pub use sparse::SparseGrid;
+pub use tiled::Tiled;
+pub use view::{GridView, GridViewMut};
+
+/// A rectangular grid, stored row by row in one `Vec`
+pub struct Grid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+}
 
 impl<T> Grid<T> {
     pub fn size(&self) -> Pos {
-        Pos::size_of(&self.0)
+        Pos {
+            x: self.width,
+            y: self.height,
+        }
     }
 
+    /// Index of `pos` in `data`, if it's in the grid
+    #[inline]
+    fn flat_index(&self, pos: &Pos) -> Option<usize> {
+        (pos.x < self.width && pos.y < self.height).then(|| pos.y * self.width + pos.x)
+    }
+
+    #[inline]
     pub fn value(&self, pos: &Pos) -> Option<&T> {
-        self.0.get(pos.y)?.get(pos.x)
+        self.data.get(self.flat_index(pos)?)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, pos: &Pos) -> Option<&mut T> {
+        let idx = self.flat_index(pos)?;
+        self.data.get_mut(idx)
     }
 
+    #[inline]
     pub fn set(&mut self, pos: &Pos, value: T) {
-        self.0[pos.y][pos.x] = value;
+        let idx = self
+            .flat_index(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a grid of {:?}", self.size()));
+        self.data[idx] = value;
     }
 
     pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> + '_ {
-        self.0.iter().map(|r| r.iter())
+        self.row_slices().map(|r| r.iter())
     }
 
     pub fn row(&self, idx: usize) -> impl Iterator<Item = &T> {
-        self.0[idx].iter()
+        self.row_slice(idx).iter()
+    }
+
+    pub fn row_slices(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks_exact` panics on a zero width, which an empty grid has
+        self.data.chunks_exact(self.width.max(1))
+    }
+
+    pub fn row_slice(&self, idx: usize) -> &[T] {
+        &self.data[idx * self.width..(idx + 1) * self.width]
+    }
+
+    pub fn row_slice_mut(&mut self, idx: usize) -> &mut [T] {
+        &mut self.data[idx * self.width..(idx + 1) * self.width]
     }
 
     pub fn col(&self, idx: usize) -> impl Iterator<Item = &T> + '_ {
-        self.0.iter().map(move |row| &row[idx])
+        assert!(idx < self.width, "column {idx} is outside the grid");
+        self.data[idx..].iter().step_by(self.width)
     }
 
     pub fn scan(&self) -> impl Iterator<Item = Pos> {
         let size = self.size();
-        (0..size.y)
-            .map(move |y| (0..size.x).map(move |x| Pos::from((x, y))))
-            .flatten()
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| Pos::from((x, y))))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter_with_pos(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.scan().zip(self.data.iter())
+    }
+
+    pub fn iter_mut_with_pos(&mut self) -> impl Iterator<Item = (Pos, &mut T)> {
+        self.scan().zip(self.data.iter_mut())
+    }
+
+    /// Positions of the cells matching `pred`, row by row
+    pub fn positions_of<'a>(
+        &'a self,
+        pred: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        self.iter_with_pos()
+            .filter(move |(_, t)| pred(t))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Position of the first cell that's `value`
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.positions_of(|t| t == value).next()
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn map_with_pos<U>(&self, mut f: impl FnMut(Pos, &T) -> U) -> Grid<U> {
+        Grid {
+            data: self.iter_with_pos().map(|(pos, t)| f(pos, t)).collect(),
+            width: self.width,
+            height: self.height,
+        }
     }
 
     pub fn walk(&self, pos: &Pos, direction: Direction, dist: usize) -> Option<Pos> {
@@ -51,11 +153,11 @@ impl<T> Grid<T> {
                 x: pos.x,
                 y: pos.y - 1,
             }),
-            Direction::East => (pos.x < size.x - 1).then(|| Pos {
+            Direction::East => (pos.x + 1 < size.x).then(|| Pos {
                 x: pos.x + 1,
                 y: pos.y,
             }),
-            Direction::South => (pos.y < size.y - 1).then(|| Pos {
+            Direction::South => (pos.y + 1 < size.y).then(|| Pos {
                 x: pos.x,
                 y: pos.y + 1,
             }),
@@ -66,6 +168,50 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Like `step`, but in any of the eight directions
+    pub fn step8(&self, pos: &Pos, direction: Direction8) -> Option<Pos> {
+        let (dx, dy) = direction.offset();
+        let pos = Pos {
+            x: pos.x.checked_add_signed(dx)?,
+            y: pos.y.checked_add_signed(dy)?,
+        };
+        self.flat_index(&pos).map(|_| pos)
+    }
+
+    /// The orthogonal neighbors of `pos` that are in the grid
+    pub fn neighbors4(&self, pos: &Pos) -> impl Iterator<Item = Pos> + '_ {
+        let pos = *pos;
+        Direction::all()
+            .into_iter()
+            .filter_map(move |d| self.step(&pos, d))
+    }
+
+    /// The neighbors of `pos` that are in the grid, diagonals included
+    pub fn neighbors8(&self, pos: &Pos) -> impl Iterator<Item = Pos> + '_ {
+        let pos = *pos;
+        Direction8::all()
+            .into_iter()
+            .filter_map(move |d| self.step8(&pos, d))
+    }
+
+    /// The cells around a horizontal run of `len` cells starting at `pos`,
+    /// diagonals included: the row above, the cells either side, then the row
+    /// below
+    pub fn neighbors_of_span(&self, pos: &Pos, len: usize) -> impl Iterator<Item = Pos> + '_ {
+        let Pos { x, y } = *pos;
+        let left = x.saturating_sub(1);
+        let right = (x + len).min(self.width.saturating_sub(1));
+        let above = y.checked_sub(1).into_iter();
+        let below = (y + 1 < self.height).then_some(y + 1).into_iter();
+
+        let row = move |y| (left..=right).map(move |x| Pos { x, y });
+        let sides = [x.checked_sub(1), Some(x + len).filter(|&x| x < self.width)]
+            .into_iter()
+            .flatten()
+            .map(move |x| Pos { x, y });
+        above.flat_map(row).chain(sides).chain(below.flat_map(row))
+    }
+
     pub fn step_unchecked(&self, pos: &Pos, direction: Direction) -> Pos {
         match direction {
             Direction::North => Pos {
@@ -88,10 +234,13 @@ impl<T> Grid<T> {
     }
 
     pub fn debug_print(&self, f: impl Fn(Pos, &T) -> String) {
+        if !log::log_enabled!(log::Level::Debug) {
+            return;
+        }
         for (y, row) in self.rows().enumerate() {
             let data = row
                 .enumerate()
-                .map(|(x, t)| f(Pos::from((x, y)), &t))
+                .map(|(x, t)| f(Pos::from((x, y)), t))
                 .join("");
             log::debug!("{data}");
         }
@@ -103,40 +252,10 @@ where
     T: Clone,
 {
     pub fn new(width: usize, height: usize, value: T) -> Self {
-        Grid(
-            (0..height)
-                .map(|_| std::iter::repeat(value.clone()).take(width).collect_vec())
-                .collect_vec(),
-        )
-    }
-}
-
-impl<T> Grid<T>
-where
-    T: Clone + PartialEq,
-{
-    pub fn flood_fill(&mut self, start: Pos, value: T, is_boundary: impl Fn(&Self, &Pos) -> bool) {
-        type D = Direction;
-        let mut next = vec![start];
-        let bounds = self.size();
-        while let Some(pos) = next.pop() {
-            self.set(&pos, value.clone());
-            for d in [D::North, D::East, D::West, D::South] {
-                let Some(p) = self.step(&pos, d) else {
-                    continue;
-                };
-                if p.x >= bounds.x || p.y >= bounds.y {
-                    continue;
-                }
-
-                if Some(&value) == self.value(&p) {
-                    continue;
-                }
-                if is_boundary(&self, &p) {
-                    continue;
-                }
-                next.push(p);
-            }
+        Grid {
+            data: vec![value; width * height],
+            width,
+            height,
         }
     }
 }
@@ -161,42 +280,69 @@ where
             return *pos;
         }
 
-        // expand in the specified direction
+        // grow in the specified direction, moving the contents if it's at the start
         let size = self.size();
         let mut pos = *pos;
         match dir {
             Direction::South => {
                 let dist = dist - (size.y - (pos.y + 1));
-                for _ in 0..dist {
-                    self.0.push(vec![T::default(); size.x]);
-                }
+                self.resize(size.x, size.y + dist, Pos::ZERO);
             }
             Direction::North => {
-                // push new rows onto the end, then rotate them to the front
                 let dist = dist - pos.y;
-                for _ in 0..dist {
-                    self.0.push(vec![T::default(); size.x]);
-                }
-                self.0.rotate_right(dist);
+                self.resize(size.x, size.y + dist, Pos { x: 0, y: dist });
                 pos.y += dist;
             }
             Direction::East => {
                 let dist = dist - (size.x - (pos.x + 1));
-                for row in self.0.iter_mut() {
-                    row.extend(std::iter::repeat(T::default()).take(dist));
-                }
+                self.resize(size.x + dist, size.y, Pos::ZERO);
             }
             Direction::West => {
                 let dist = dist - pos.x;
-                for row in self.0.iter_mut() {
-                    row.extend(std::iter::repeat(T::default()).take(dist));
-                    row.rotate_right(dist);
-                }
+                self.resize(size.x + dist, size.y, Pos { x: dist, y: 0 });
                 pos.x += dist;
             }
         }
 
-        return pos;
+        pos
+    }
+
+    /// Grow the grid to `width` by `height`, with the old contents at `offset`
+    /// and default values everywhere else
+    fn resize(&mut self, width: usize, height: usize, offset: Pos) {
+        if offset == Pos::ZERO && width == self.width {
+            self.data.resize(width * height, T::default());
+            self.height = height;
+            return;
+        }
+
+        let mut data = vec![T::default(); width * height];
+        for (y, row) in self.row_slices().enumerate() {
+            let start = (y + offset.y) * width + offset.x;
+            data[start..start + row.len()].clone_from_slice(row);
+        }
+        *self = Self {
+            data,
+            width,
+            height,
+        };
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.value(&pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a grid of {:?}", self.size()))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let size = self.size();
+        self.get_mut(&pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a grid of {size:?}"))
     }
 }
 
@@ -205,7 +351,7 @@ where
     T: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.width == other.width && self.data == other.data
     }
 }
 
@@ -216,7 +362,8 @@ where
     T: Hash,
 {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.0.hash(state)
+        self.width.hash(state);
+        self.data.hash(state)
     }
 }
 
@@ -225,7 +372,11 @@ where
     T: Clone,
 {
     fn clone(&self) -> Self {
-        Self(self.0.clone())
+        Self {
+            data: self.data.clone(),
+            width: self.width,
+            height: self.height,
+        }
     }
 }
 
@@ -244,22 +395,265 @@ where
     }
 }
 
+/// Why some text isn't a grid. Rows and columns count from zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// A character that couldn't be turned into a cell
+    Cell {
+        pos: Pos,
+        found: char,
+        message: String,
+    },
+    /// A row that isn't as wide as the first one
+    Ragged {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+    Empty,
+}
+
+impl GridError {
+    /// Shift the error down by `rows`, for a grid that doesn't start at the
+    /// top of the input
+    fn offset(self, rows: usize) -> Self {
+        match self {
+            Self::Cell {
+                pos,
+                found,
+                message,
+            } => Self::Cell {
+                pos: Pos {
+                    x: pos.x,
+                    y: pos.y + rows,
+                },
+                found,
+                message,
+            },
+            Self::Ragged {
+                row,
+                width,
+                expected,
+            } => Self::Ragged {
+                row: row + rows,
+                width,
+                expected,
+            },
+            Self::Empty => Self::Empty,
+        }
+    }
+}
+
+impl std::fmt::Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cell {
+                pos,
+                found,
+                message,
+            } => write!(
+                f,
+                "row {}, column {}: can't parse {found:?}: {message}",
+                pos.y + 1,
+                pos.x + 1
+            ),
+            Self::Ragged {
+                row,
+                width,
+                expected,
+            } => write!(
+                f,
+                "row {} is {width} cells wide, expected {expected}",
+                row + 1
+            ),
+            Self::Empty => write!(f, "grid is empty"),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl<T> Grid<T> {
+    /// Parse a grid with one row per line, turning each character into a cell
+    /// with `parse`
+    pub fn parse_with<E: std::fmt::Display>(
+        input: &str,
+        parse: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, GridError> {
+        Self::parse_rows(input.lines(), parse)
+    }
+
+    /// Parse a grid from rows that have already been split up, for grids
+    /// that aren't one row per line
+    pub fn parse_rows<'a, E: std::fmt::Display>(
+        rows: impl IntoIterator<Item = &'a str>,
+        mut parse: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, GridError> {
+        let mut data = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (y, row) in rows.into_iter().enumerate() {
+            let row = row
+                .chars()
+                .enumerate()
+                .map(|(x, c)| {
+                    parse(c).map_err(|e| GridError::Cell {
+                        pos: Pos { x, y },
+                        found: c,
+                        message: e.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let expected = *width.get_or_insert(row.len());
+            if row.len() != expected {
+                return Err(GridError::Ragged {
+                    row: y,
+                    width: row.len(),
+                    expected,
+                });
+            }
+            data.extend(row);
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                data,
+                width,
+                height,
+            }),
+            _ => Err(GridError::Empty),
+        }
+    }
+
+    /// Parse several grids separated by blank lines. Errors count rows from
+    /// the top of the input.
+    pub fn parse_many<E: std::fmt::Display>(
+        input: &str,
+        mut parse: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Vec<Self>, GridError> {
+        let mut offset = 0;
+        input
+            .split("\n\n")
+            .map(|block| {
+                let grid = Self::parse_with(block, &mut parse).map_err(|e| e.offset(offset));
+                offset += block.lines().count() + 1;
+                grid
+            })
+            .collect()
+    }
+}
+
 impl<T> FromStr for Grid<T>
 where
-    T: FromStr,
-    T::Err: std::fmt::Debug,
+    T: TryFrom<char>,
+    T::Error: std::fmt::Display,
 {
-    type Err = anyhow::Error;
+    type Err = GridError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let data = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_string().parse().unwrap())
-                    .collect_vec()
-            })
-            .collect_vec();
-        Ok(Self(data))
+        Self::parse_with(s, T::try_from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digit(c: char) -> Result<u32, &'static str> {
+        c.to_digit(10).ok_or("not a digit")
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse_with("12\n34\n", digit).unwrap();
+        assert_eq!(grid.size(), Pos { x: 2, y: 2 });
+        assert_eq!(grid.value(&Pos { x: 0, y: 1 }), Some(&3));
+
+        let grid = Grid::parse_rows("12/34".split('/'), digit).unwrap();
+        assert_eq!(grid.row(1).copied().collect_vec(), [3, 4]);
+
+        let err = Grid::parse_with("12\n3x\n", digit).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "row 2, column 2: can't parse 'x': not a digit"
+        );
+
+        let err = Grid::parse_with("12\n345\n", digit).unwrap_err();
+        assert_eq!(err.to_string(), "row 2 is 3 cells wide, expected 2");
+        assert_eq!(Grid::parse_with("", digit), Err(GridError::Empty));
+    }
+
+    #[test]
+    fn test_make_space() {
+        let mut grid = Grid::parse_with("12\n34\n", digit).unwrap();
+        let pos = grid.make_space(&Pos { x: 0, y: 0 }, Direction::North, 1);
+        assert_eq!(pos, Pos { x: 0, y: 1 });
+        let pos = grid.make_space(&pos, Direction::West, 2);
+        assert_eq!(pos, Pos { x: 2, y: 1 });
+        grid.make_space(&Pos { x: 3, y: 2 }, Direction::East, 1);
+        grid.make_space(&Pos { x: 0, y: 2 }, Direction::South, 1);
+
+        assert_eq!(grid.size(), Pos { x: 5, y: 4 });
+        assert_eq!(grid.row_slice(1), [0, 0, 1, 2, 0]);
+        assert_eq!(grid.row_slice(2), [0, 0, 3, 4, 0]);
+        assert_eq!(grid.col(3).copied().collect_vec(), [0, 2, 4, 0]);
+        assert_eq!(grid.value(&Pos { x: 5, y: 0 }), None);
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = Grid::parse_with("12\n31\n", digit).unwrap();
+        assert_eq!(grid[Pos { x: 1, y: 0 }], 2);
+        grid[Pos { x: 1, y: 0 }] = 5;
+        *grid.get_mut(&Pos { x: 0, y: 0 }).unwrap() += 1;
+        assert_eq!(grid.row_slice(0), [2, 5]);
+
+        assert_eq!(grid.find(&3), Some(Pos { x: 0, y: 1 }));
+        assert_eq!(grid.find(&7), None);
+        let odd = grid.positions_of(|v| v % 2 == 1).collect_vec();
+        assert_eq!(
+            odd,
+            [Pos { x: 1, y: 0 }, Pos { x: 0, y: 1 }, Pos { x: 1, y: 1 }]
+        );
+
+        for (pos, v) in grid.iter_mut_with_pos() {
+            *v += pos.y as u32 * 10;
+        }
+        let sums = grid.map_with_pos(|pos, v| pos.x as u32 + v);
+        assert_eq!(sums.row_slice(1), [13, 12]);
+        assert_eq!(grid.map(|v| v * 2).col(0).copied().collect_vec(), [4, 26]);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(4, 3, 0);
+        let corner = grid.neighbors8(&Pos::ZERO).collect_vec();
+        assert_eq!(corner, [(1, 0), (1, 1), (0, 1)].map(Pos::from));
+        assert_eq!(grid.neighbors4(&Pos { x: 1, y: 1 }).count(), 4);
+        assert_eq!(grid.neighbors8(&Pos { x: 3, y: 1 }).count(), 5);
+
+        let span = grid.neighbors_of_span(&Pos { x: 1, y: 1 }, 2).collect_vec();
+        assert_eq!(span.len(), 10);
+        assert_eq!(span[0], Pos { x: 0, y: 0 });
+        assert_eq!(span[4..6], [Pos { x: 0, y: 1 }, Pos { x: 3, y: 1 }]);
+        let edge = grid.neighbors_of_span(&Pos { x: 2, y: 0 }, 2).collect_vec();
+        assert_eq!(edge, [(1, 0), (1, 1), (2, 1), (3, 1)].map(Pos::from));
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let grids = Grid::parse_many("12\n34\n\n5\n6\n", digit).unwrap();
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[1].size(), Pos { x: 1, y: 2 });
+
+        let err = Grid::parse_many("12\n34\n\n5\nx\n", digit).unwrap_err();
+        assert!(matches!(
+            err,
+            GridError::Cell {
+                pos: Pos { x: 0, y: 4 },
+                ..
+            }
+        ));
     }
 }
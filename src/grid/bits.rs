@@ -0,0 +1,222 @@
+use super::Grid;
+use crate::Pos;
+use itertools::Itertools;
+
+const WORD: usize = u64::BITS as usize;
+
+/// A grid of booleans packed into bits, each row starting on a new word so
+/// whole rows can be compared and counted a word at a time
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    /// Words per row
+    stride: usize,
+    width: usize,
+    height: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(WORD);
+        BitGrid {
+            words: vec![0; stride * height],
+            stride,
+            width,
+            height,
+        }
+    }
+
+    /// Set the bits for the cells of `grid` matching `pred`
+    pub fn from_grid<T>(grid: &Grid<T>, pred: impl Fn(&T) -> bool) -> Self {
+        let size = grid.size();
+        let mut bits = Self::new(size.x, size.y);
+        for pos in grid.positions_of(pred) {
+            bits.set(&pos, true);
+        }
+        bits
+    }
+
+    pub fn size(&self) -> Pos {
+        Pos {
+            x: self.width,
+            y: self.height,
+        }
+    }
+
+    /// Index of the word holding `pos`, and the bit within it
+    #[inline]
+    fn locate(&self, pos: &Pos) -> (usize, u64) {
+        assert!(
+            pos.x < self.width && pos.y < self.height,
+            "{pos:?} is outside a grid of {:?}",
+            self.size()
+        );
+        (pos.y * self.stride + pos.x / WORD, 1 << (pos.x % WORD))
+    }
+
+    pub fn get(&self, pos: &Pos) -> bool {
+        let (idx, bit) = self.locate(pos);
+        self.words[idx] & bit != 0
+    }
+
+    pub fn set(&mut self, pos: &Pos, value: bool) {
+        let (idx, bit) = self.locate(pos);
+        if value {
+            self.words[idx] |= bit;
+        } else {
+            self.words[idx] &= !bit;
+        }
+    }
+
+    /// Set the bit at `pos`, returning whether it was clear before
+    pub fn insert(&mut self, pos: &Pos) -> bool {
+        let (idx, bit) = self.locate(pos);
+        let was_clear = self.words[idx] & bit == 0;
+        self.words[idx] |= bit;
+        was_clear
+    }
+
+    fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// How many bits are set
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn row_count(&self, y: usize) -> usize {
+        self.row_words(y)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn col_count(&self, x: usize) -> usize {
+        (0..self.height)
+            .filter(|&y| self.get(&Pos { x, y }))
+            .count()
+    }
+
+    pub fn row_eq(&self, a: usize, b: usize) -> bool {
+        self.row_words(a) == self.row_words(b)
+    }
+
+    pub fn col_eq(&self, a: usize, b: usize) -> bool {
+        self.col_diff(a, b) == 0
+    }
+
+    /// How many cells differ between rows `a` and `b`
+    pub fn row_diff(&self, a: usize, b: usize) -> usize {
+        self.row_words(a)
+            .iter()
+            .zip(self.row_words(b))
+            .map(|(a, b)| (a ^ b).count_ones() as usize)
+            .sum()
+    }
+
+    /// How many cells differ between columns `a` and `b`
+    pub fn col_diff(&self, a: usize, b: usize) -> usize {
+        (0..self.height)
+            .filter(|&y| self.get(&Pos { x: a, y }) != self.get(&Pos { x: b, y }))
+            .count()
+    }
+
+    /// The same bits with rows and columns swapped, so columns can be
+    /// compared a word at a time
+    pub fn transposed(&self) -> Self {
+        let mut bits = Self::new(self.height, self.width);
+        for pos in self.ones() {
+            bits.set(&Pos { x: pos.y, y: pos.x }, true);
+        }
+        bits
+    }
+
+    /// Positions of the set bits, row by row
+    pub fn ones(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.words.iter().enumerate().flat_map(move |(idx, &word)| {
+            let y = idx / self.stride;
+            let base = (idx % self.stride) * WORD;
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(Pos { x: base + bit, y })
+            })
+        })
+    }
+
+    /// Set every bit that's set in `other`
+    pub fn union_with(&mut self, other: &Self) {
+        self.assert_same_size(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    /// Clear every bit that isn't set in `other`
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.assert_same_size(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    /// Log the grid at debug level, drawing each cell with `f`
+    pub fn debug_print(&self, f: impl Fn(Pos, bool) -> String) {
+        if !log::log_enabled!(log::Level::Debug) {
+            return;
+        }
+        for y in 0..self.height {
+            let data = (0..self.width)
+                .map(|x| {
+                    let pos = Pos { x, y };
+                    f(pos, self.get(&pos))
+                })
+                .join("");
+            log::debug!("{data}");
+        }
+    }
+
+    fn assert_same_size(&self, other: &Self) {
+        assert_eq!(self.size(), other.size(), "grids are different sizes");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bits() {
+        // Wide enough to need two words a row
+        let row = |s: &str| format!("{s}{}", ".".repeat(64));
+        let text = [row("#.#"), row("#.."), row("#.#")].join("\n");
+        let grid = Grid::parse_with(&text, |c| Ok::<_, &str>(c)).unwrap();
+        let mut bits = BitGrid::from_grid(&grid, |&c| c == '#');
+        bits.set(&Pos { x: 66, y: 1 }, true);
+
+        assert_eq!(bits.count_ones(), 6);
+        assert_eq!((bits.row_count(1), bits.col_count(0)), (2, 3));
+        assert!(bits.row_eq(0, 2));
+        assert_eq!(bits.row_diff(0, 1), 2);
+        assert_eq!(bits.col_diff(0, 2), 1);
+        assert!(!bits.col_eq(1, 66));
+        assert_eq!(bits.transposed().row_diff(0, 2), 1);
+        assert_eq!(bits.ones().nth(4), Some(Pos { x: 0, y: 2 }));
+        assert!(!bits.insert(&Pos { x: 2, y: 0 }));
+        assert!(bits.insert(&Pos { x: 1, y: 0 }));
+
+        let mut other = BitGrid::new(67, 3);
+        other.set(&Pos { x: 1, y: 1 }, true);
+        other.set(&Pos { x: 0, y: 0 }, true);
+        let mut both = bits.clone();
+        both.intersect_with(&other);
+        assert_eq!(both.ones().collect_vec(), [Pos::ZERO]);
+        bits.union_with(&other);
+        assert_eq!(bits.count_ones(), 8);
+    }
+}
@@ -0,0 +1,130 @@
+use super::{Grid, Tiled};
+use crate::{Point, Pos};
+use std::collections::{HashMap, VecDeque};
+
+impl<T> Grid<T> {
+    /// Fewest steps from `start` to every cell, only moving between
+    /// orthogonal neighbors that are `passable`. Unreachable cells are `None`.
+    pub fn bfs_distances(
+        &self,
+        start: Pos,
+        passable: impl Fn(&Pos, &T) -> bool,
+    ) -> Grid<Option<usize>> {
+        self.bfs_distances_from([start], passable)
+    }
+
+    /// Like `bfs_distances`, but counting from whichever start is closest
+    pub fn bfs_distances_from(
+        &self,
+        starts: impl IntoIterator<Item = Pos>,
+        passable: impl Fn(&Pos, &T) -> bool,
+    ) -> Grid<Option<usize>> {
+        let mut dist = self.map(|_| None);
+        let mut next = VecDeque::new();
+        for start in starts {
+            dist[start] = Some(0);
+            next.push_back(start);
+        }
+
+        while let Some(pos) = next.pop_front() {
+            let d = dist[pos].unwrap() + 1;
+            for n in self.neighbors4(&pos) {
+                if dist[n].is_none() && passable(&n, &self[n]) {
+                    dist[n] = Some(d);
+                    next.push_back(n);
+                }
+            }
+        }
+        dist
+    }
+
+    /// The cells where a walk of exactly `steps` from `start` can end.
+    /// Stepping back and forth wastes two steps, so that's every cell at most
+    /// `steps` away whose distance has the same parity.
+    pub fn reachable_in_exactly(
+        &self,
+        start: Pos,
+        steps: usize,
+        passable: impl Fn(&Pos, &T) -> bool,
+    ) -> Vec<Pos> {
+        let dist = self.bfs_distances(start, passable);
+        dist.positions_of(|d| d.is_some_and(|d| d <= steps && d % 2 == steps % 2))
+            .collect()
+    }
+}
+
+impl<T> Tiled<'_, T> {
+    /// Fewest steps from `start` to every point up to `limit` steps away,
+    /// crossing into the neighboring copies of the grid as needed
+    pub fn bfs_distances(
+        &self,
+        start: Point,
+        limit: usize,
+        passable: impl Fn(&Point, &T) -> bool,
+    ) -> HashMap<Point, usize> {
+        let mut dist = HashMap::from([(start, 0)]);
+        let mut next = VecDeque::from([start]);
+        while let Some(point) = next.pop_front() {
+            let d = dist[&point] + 1;
+            if d > limit {
+                continue;
+            }
+            for n in self.neighbors(&point) {
+                if !dist.contains_key(&n) && passable(&n, self.value(&n)) {
+                    dist.insert(n, d);
+                    next.push_back(n);
+                }
+            }
+        }
+        dist
+    }
+
+    /// The points where a walk of exactly `steps` can end, across all copies
+    /// of the grid. Group them by `tile` to count how many are in each copy.
+    pub fn reachable_in_exactly(
+        &self,
+        start: Point,
+        steps: usize,
+        passable: impl Fn(&Point, &T) -> bool,
+    ) -> Vec<Point> {
+        let mut points = self
+            .bfs_distances(start, steps, passable)
+            .into_iter()
+            .filter(|(_, d)| d % 2 == steps % 2)
+            .map(|(p, _)| p)
+            .collect::<Vec<_>>();
+        points.sort();
+        points
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_distances() {
+        let grid = Grid::parse_with("...\n.#.\n...\n", |c| Ok::<_, &str>(c)).unwrap();
+        let open = |_: &Pos, c: &char| *c == '.';
+
+        let dist = grid.bfs_distances(Pos::ZERO, open);
+        assert_eq!(dist[Pos { x: 2, y: 2 }], Some(4));
+        assert_eq!(dist[Pos { x: 1, y: 1 }], None);
+
+        let corners = [Pos::ZERO, Pos { x: 2, y: 2 }];
+        let dist = grid.bfs_distances_from(corners, open);
+        assert_eq!(dist.iter_with_pos().filter_map(|(_, d)| *d).max(), Some(2));
+
+        assert_eq!(grid.reachable_in_exactly(Pos::ZERO, 2, open).len(), 3);
+        assert_eq!(grid.reachable_in_exactly(Pos::ZERO, 3, open).len(), 4);
+
+        // On the tiled grid the walls stop nothing from going around them
+        let tiled = grid.tiled();
+        let reached = tiled.reachable_in_exactly(Point::new(1, 0), 6, |_, c| *c == '.');
+        let per_tile = reached.iter().counts_by(|p| tiled.tile(p));
+        assert_eq!(per_tile[&Point::ZERO], 4);
+        assert!(per_tile.contains_key(&Point::new(0, -1)));
+        assert_eq!(per_tile.values().sum::<usize>(), reached.len());
+    }
+}
@@ -0,0 +1,224 @@
+use super::{Connectivity, Grid, Region, Regions};
+use crate::{Direction, Point, Pos};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// A position on a `GridMap`
+pub trait Coord: Copy + Eq + Hash + Debug {
+    /// One step in `direction`, unless that leaves the coordinate space
+    fn step(&self, direction: Direction) -> Option<Self>;
+
+    /// The heading when moving to an adjacent coord
+    fn heading(&self, to: &Self) -> Direction;
+
+    /// The smaller x and the smaller y of the two, for bounding boxes
+    fn componentwise_min(&self, other: &Self) -> Self;
+
+    fn componentwise_max(&self, other: &Self) -> Self;
+}
+
+impl Coord for Pos {
+    fn step(&self, direction: Direction) -> Option<Self> {
+        let Pos { x, y } = *self;
+        Some(match direction {
+            Direction::North => Pos {
+                x,
+                y: y.checked_sub(1)?,
+            },
+            Direction::East => Pos { x: x + 1, y },
+            Direction::South => Pos { x, y: y + 1 },
+            Direction::West => Pos {
+                x: x.checked_sub(1)?,
+                y,
+            },
+        })
+    }
+
+    fn heading(&self, to: &Self) -> Direction {
+        Direction::heading(*self, *to)
+    }
+
+    fn componentwise_min(&self, other: &Self) -> Self {
+        Pos {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+        }
+    }
+
+    fn componentwise_max(&self, other: &Self) -> Self {
+        Pos {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+        }
+    }
+}
+
+impl Coord for Point {
+    fn step(&self, direction: Direction) -> Option<Self> {
+        Some(*self + direction.into())
+    }
+
+    fn heading(&self, to: &Self) -> Direction {
+        match (to.x - self.x, to.y - self.y) {
+            (0, -1) => Direction::North,
+            (1, 0) => Direction::East,
+            (0, 1) => Direction::South,
+            (-1, 0) => Direction::West,
+            _ => panic!("no direction {self:?} {to:?}"),
+        }
+    }
+
+    fn componentwise_min(&self, other: &Self) -> Self {
+        Point::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    fn componentwise_max(&self, other: &Self) -> Self {
+        Point::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
+
+/// What `Grid` and `SparseGrid` have in common, so an algorithm can be written
+/// once for both
+pub trait GridMap {
+    type Coord: Coord;
+    type Cell;
+
+    fn value(&self, pos: &Self::Coord) -> Option<&Self::Cell>;
+
+    fn set(&mut self, pos: &Self::Coord, value: Self::Cell);
+
+    /// The top left and bottom right corners, or `None` if there's nothing in
+    /// the grid
+    fn bounds(&self) -> Option<(Self::Coord, Self::Coord)>;
+
+    /// Whether `pos` is within the bounds
+    fn contains(&self, pos: &Self::Coord) -> bool;
+
+    /// Every position within the bounds, row by row
+    fn scan(&self) -> impl Iterator<Item = Self::Coord> + '_;
+
+    /// The positions of the cells that are set, row by row. Algorithms that
+    /// start from what's in the grid use this rather than `scan`, so a sparse
+    /// grid doesn't walk its whole bounding box.
+    fn cells(&self) -> impl Iterator<Item = Self::Coord> + '_ {
+        self.scan().filter(|pos| self.value(pos).is_some())
+    }
+
+    /// Draw the bounding box a line per row, with `None` for cells that aren't
+    /// set
+    fn render(&self, f: impl Fn(Option<&Self::Cell>) -> char) -> String;
+
+    fn step(&self, pos: &Self::Coord, direction: Direction) -> Option<Self::Coord> {
+        pos.step(direction).filter(|p| self.contains(p))
+    }
+
+    /// The orthogonal neighbors of `pos` within the bounds
+    fn neighbors(&self, pos: &Self::Coord) -> impl Iterator<Item = Self::Coord> + '_ {
+        let pos = *pos;
+        Direction::all()
+            .into_iter()
+            .filter_map(move |d| self.step(&pos, d))
+    }
+
+    /// Label the connected groups of set cells matching `pred`, and measure
+    /// each one. Unlike `flood_fill`, the grid isn't changed.
+    fn regions(
+        &self,
+        connectivity: Connectivity,
+        pred: impl Fn(&Self, &Self::Coord) -> bool,
+    ) -> Regions<Self::Coord>
+    where
+        Self: Sized,
+    {
+        super::region::label(self, connectivity, pred)
+    }
+
+    /// Measure the connected group of cells matching `pred` around `start`,
+    /// set or not. Only that group is visited, with the bounds as the outer
+    /// limit.
+    fn region(
+        &self,
+        start: Self::Coord,
+        connectivity: Connectivity,
+        pred: impl Fn(&Self, &Self::Coord) -> bool,
+    ) -> Region<Self::Coord>
+    where
+        Self: Sized,
+    {
+        super::region::measure(self, start, connectivity, pred)
+    }
+
+    /// Set `value` on every cell reachable from `start` without crossing the
+    /// boundary or leaving the bounds
+    fn flood_fill(
+        &mut self,
+        start: Self::Coord,
+        value: Self::Cell,
+        is_boundary: impl Fn(&Self, &Self::Coord) -> bool,
+    ) where
+        Self::Cell: Clone + PartialEq,
+    {
+        let mut next = vec![start];
+        while let Some(pos) = next.pop() {
+            self.set(&pos, value.clone());
+            for d in Direction::all() {
+                let Some(p) = self.step(&pos, d) else {
+                    continue;
+                };
+                if Some(&value) == self.value(&p) {
+                    continue;
+                }
+                if is_boundary(self, &p) {
+                    continue;
+                }
+                next.push(p);
+            }
+        }
+    }
+}
+
+impl<T> GridMap for Grid<T> {
+    type Coord = Pos;
+    type Cell = T;
+
+    fn value(&self, pos: &Pos) -> Option<&T> {
+        Grid::value(self, pos)
+    }
+
+    fn set(&mut self, pos: &Pos, value: T) {
+        Grid::set(self, pos, value)
+    }
+
+    fn bounds(&self) -> Option<(Pos, Pos)> {
+        let size = self.size();
+        (size.x > 0 && size.y > 0).then(|| {
+            let end = Pos {
+                x: size.x - 1,
+                y: size.y - 1,
+            };
+            (Pos::ZERO, end)
+        })
+    }
+
+    fn contains(&self, pos: &Pos) -> bool {
+        self.flat_index(pos).is_some()
+    }
+
+    fn scan(&self) -> impl Iterator<Item = Pos> + '_ {
+        Grid::scan(self)
+    }
+
+    fn cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        Grid::scan(self)
+    }
+
+    fn render(&self, f: impl Fn(Option<&T>) -> char) -> String {
+        self.rows()
+            .map(|row| row.map(|t| f(Some(t))).chain(['\n']).collect::<String>())
+            .collect()
+    }
+
+    fn step(&self, pos: &Pos, direction: Direction) -> Option<Pos> {
+        Grid::step(self, pos, direction)
+    }
+}
@@ -0,0 +1,140 @@
+use super::Grid;
+use crate::{Direction, Pos};
+
+impl<T> Grid<T> {
+    /// The cells from `start` in `direction` up to the edge of the grid, not
+    /// including `start` itself
+    pub fn ray(&self, start: Pos, direction: Direction) -> impl Iterator<Item = (Pos, &T)> {
+        std::iter::successors(self.step(&start, direction), move |pos| {
+            self.step(pos, direction)
+        })
+        .map(|pos| (pos, &self[pos]))
+    }
+
+    /// Like `ray`, but stopping at the first cell matching `stop`. That cell
+    /// is the last one yielded, so it's easy to tell what the ray hit.
+    pub fn ray_until(
+        &self,
+        start: Pos,
+        direction: Direction,
+        stop: impl Fn(&Pos, &T) -> bool,
+    ) -> impl Iterator<Item = (Pos, &T)> {
+        let mut stopped = false;
+        self.ray(start, direction).take_while(move |(pos, t)| {
+            let go = !stopped;
+            stopped = stop(pos, t);
+            go
+        })
+    }
+
+    /// The cells on a straight line from `from` to `to`, both included,
+    /// leaving out any that are off the grid. Lines that aren't horizontal,
+    /// vertical or diagonal use Bresenham's algorithm to pick the cells
+    /// closest to the line.
+    pub fn line(&self, from: Pos, to: Pos) -> impl Iterator<Item = Pos> {
+        let size = self.size();
+        let (x0, y0) = (from.x as i64, from.y as i64);
+        let (x1, y1) = (to.x as i64, to.y as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+
+        let mut next = Some((x0, y0));
+        let mut err = dx + dy;
+        std::iter::from_fn(move || {
+            let (x, y) = next?;
+            if (x, y) == (x1, y1) {
+                next = None;
+            } else {
+                let e2 = 2 * err;
+                let (mut nx, mut ny) = (x, y);
+                if e2 >= dy {
+                    err += dy;
+                    nx += sx;
+                }
+                if e2 <= dx {
+                    err += dx;
+                    ny += sy;
+                }
+                next = Some((nx, ny));
+            }
+            Some(Pos {
+                x: x as usize,
+                y: y as usize,
+            })
+        })
+        .filter(move |pos| pos.x < size.x && pos.y < size.y)
+    }
+
+    /// Every row or column, in the order something moving in `direction`
+    /// would meet them: each line starts at the edge `direction` points to.
+    /// Going north gives the columns from left to right, each from the top
+    /// down, which is the order to tilt things north in.
+    pub fn lines(&self, direction: Direction) -> impl Iterator<Item = impl Iterator<Item = Pos>> {
+        let size = self.size();
+        let (count, len) = match direction {
+            Direction::North | Direction::South => (size.x, size.y),
+            Direction::East | Direction::West => (size.y, size.x),
+        };
+        (0..count).map(move |line| {
+            (0..len).map(move |i| match direction {
+                Direction::North => Pos { x: line, y: i },
+                Direction::South => Pos {
+                    x: line,
+                    y: len - 1 - i,
+                },
+                Direction::West => Pos { x: i, y: line },
+                Direction::East => Pos {
+                    x: len - 1 - i,
+                    y: line,
+                },
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_rays() {
+        let grid = Grid::parse_with("..#.\n....\n#...\n", |c| Ok::<_, &str>(c)).unwrap();
+        let pos = |x, y| Pos { x, y };
+
+        let east = grid.ray(pos(0, 0), Direction::East).map(|(p, _)| p);
+        assert_eq!(east.collect_vec(), [pos(1, 0), pos(2, 0), pos(3, 0)]);
+        let hit = grid.ray_until(pos(0, 0), Direction::East, |_, c| *c == '#');
+        assert_eq!(hit.last(), Some((pos(2, 0), &'#')));
+        assert_eq!(grid.ray(pos(0, 0), Direction::North).count(), 0);
+
+        assert_eq!(
+            grid.line(pos(3, 0), pos(3, 2)).collect_vec(),
+            [pos(3, 0), pos(3, 1), pos(3, 2)]
+        );
+        assert_eq!(
+            grid.line(pos(2, 2), pos(0, 0)).collect_vec(),
+            [pos(2, 2), pos(1, 1), pos(0, 0)]
+        );
+        let steep = grid.line(pos(0, 0), pos(3, 1)).collect_vec();
+        assert_eq!(steep, [pos(0, 0), pos(1, 0), pos(2, 1), pos(3, 1)]);
+        assert_eq!(grid.line(pos(1, 1), pos(1, 1)).count(), 1);
+        assert_eq!(
+            grid.line(pos(2, 1), pos(6, 1)).collect_vec(),
+            [pos(2, 1), pos(3, 1)]
+        );
+
+        let south = grid
+            .lines(Direction::South)
+            .map(|l| l.collect_vec())
+            .collect_vec();
+        assert_eq!(south.len(), 4);
+        assert_eq!(south[1], [pos(1, 2), pos(1, 1), pos(1, 0)]);
+        let east = grid
+            .lines(Direction::East)
+            .map(|l| l.collect_vec())
+            .collect_vec();
+        assert_eq!(east[2][0], pos(3, 2));
+    }
+}
@@ -0,0 +1,174 @@
+use super::{Coord, GridMap};
+use crate::Direction;
+use std::collections::{HashMap, HashSet};
+
+/// Which cells count as touching
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only cells that share an edge
+    Four,
+    /// Diagonal neighbors too
+    Eight,
+}
+
+/// One group of connected cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<C> {
+    /// The first cell found reading row by row, or where `region` started
+    pub start: C,
+    pub area: usize,
+    /// Edges between a cell in the region and one that isn't, or the outside
+    pub perimeter: usize,
+    /// Top left and bottom right corners of the bounding box
+    pub min: C,
+    pub max: C,
+    /// Whether any cell is on the edge of the grid
+    pub touches_border: bool,
+}
+
+/// Every region in a grid, and which one each cell belongs to
+#[derive(Debug, Clone)]
+pub struct Regions<C> {
+    pub labels: HashMap<C, usize>,
+    pub regions: Vec<Region<C>>,
+}
+
+impl<C: Coord> Regions<C> {
+    /// Index into `regions` of the region containing `pos`
+    pub fn label(&self, pos: &C) -> Option<usize> {
+        self.labels.get(pos).copied()
+    }
+
+    pub fn region_at(&self, pos: &C) -> Option<&Region<C>> {
+        self.label(pos).map(|l| &self.regions[l])
+    }
+}
+
+pub(super) fn label<G: GridMap>(
+    grid: &G,
+    connectivity: Connectivity,
+    pred: impl Fn(&G, &G::Coord) -> bool,
+) -> Regions<G::Coord> {
+    let member = |pos: &G::Coord| grid.value(pos).is_some() && pred(grid, pos);
+    let mut labels = HashMap::new();
+    let mut regions = vec![];
+    for start in grid.cells() {
+        if labels.contains_key(&start) || !member(&start) {
+            continue;
+        }
+
+        let label = regions.len();
+        let region = fill(grid, start, connectivity, member, |pos| {
+            labels.insert(pos, label).is_none()
+        });
+        regions.push(region);
+    }
+
+    Regions { labels, regions }
+}
+
+pub(super) fn measure<G: GridMap>(
+    grid: &G,
+    start: G::Coord,
+    connectivity: Connectivity,
+    pred: impl Fn(&G, &G::Coord) -> bool,
+) -> Region<G::Coord> {
+    let mut seen = HashSet::new();
+    fill(
+        grid,
+        start,
+        connectivity,
+        |pos| pred(grid, pos),
+        |pos| seen.insert(pos),
+    )
+}
+
+/// Measure the region of `member` cells around `start`. `claim` is called on
+/// every member found, and returns false for those already seen.
+fn fill<G: GridMap>(
+    grid: &G,
+    start: G::Coord,
+    connectivity: Connectivity,
+    member: impl Fn(&G::Coord) -> bool,
+    mut claim: impl FnMut(G::Coord) -> bool,
+) -> Region<G::Coord> {
+    let mut region = Region {
+        start,
+        area: 0,
+        perimeter: 0,
+        min: start,
+        max: start,
+        touches_border: false,
+    };
+    claim(start);
+    let mut next = vec![start];
+    while let Some(pos) = next.pop() {
+        region.area += 1;
+        region.min = region.min.componentwise_min(&pos);
+        region.max = region.max.componentwise_max(&pos);
+        for d in Direction::all() {
+            match grid.step(&pos, d) {
+                None => {
+                    region.touches_border = true;
+                    region.perimeter += 1;
+                }
+                Some(n) if !member(&n) => region.perimeter += 1,
+                Some(_) => {}
+            }
+        }
+
+        for n in neighbors(grid, &pos, connectivity) {
+            if member(&n) && claim(n) {
+                next.push(n);
+            }
+        }
+    }
+    region
+}
+
+fn neighbors<G: GridMap>(grid: &G, pos: &G::Coord, connectivity: Connectivity) -> Vec<G::Coord> {
+    let mut found = grid.neighbors(pos).collect::<Vec<_>>();
+    if connectivity == Connectivity::Eight {
+        // The corners, going clockwise from the north east
+        found.extend(Direction::all().into_iter().filter_map(|d| {
+            let corner = pos.step(d)?.step(d.turn_right())?;
+            grid.contains(&corner).then_some(corner)
+        }));
+    }
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Grid, Pos};
+
+    #[test]
+    fn test_regions() {
+        let grid = Grid::parse_with("##..\n#..#\n..#.\n", |c| Ok::<_, &str>(c)).unwrap();
+        let wall = |g: &Grid<char>, p: &Pos| g[*p] == '#';
+
+        let four = grid.regions(Connectivity::Four, wall);
+        assert_eq!(four.regions.len(), 3);
+        let corner = four.region_at(&Pos::ZERO).unwrap();
+        assert_eq!((corner.area, corner.perimeter), (3, 8));
+        assert_eq!((corner.min, corner.max), (Pos::ZERO, Pos { x: 1, y: 1 }));
+        assert!(corner.touches_border);
+        assert_eq!(four.label(&Pos { x: 2, y: 0 }), None);
+
+        let eight = grid.regions(Connectivity::Eight, wall);
+        assert_eq!(eight.regions.len(), 2);
+        assert_eq!(eight.regions[1].area, 2);
+
+        let open = grid.regions(Connectivity::Four, |g, p| g[*p] == '.');
+        assert_eq!(open.regions.len(), 2);
+        assert_eq!(open.region_at(&Pos { x: 3, y: 2 }).unwrap().area, 1);
+        let one = grid.region(Pos { x: 3, y: 0 }, Connectivity::Four, |g, p| g[*p] == '.');
+        let labelled = open.region_at(&Pos { x: 2, y: 0 }).unwrap();
+        assert_eq!(
+            (one.area, one.perimeter),
+            (labelled.area, labelled.perimeter)
+        );
+        assert_eq!((one.min, one.max), (labelled.min, labelled.max));
+    }
+}
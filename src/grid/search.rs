@@ -0,0 +1,152 @@
+use super::Grid;
+use crate::{Direction, Pos};
+use pathfinding::directed::{astar::astar, bfs::bfs, dijkstra::dijkstra};
+use std::hash::Hash;
+
+/// Where a search is on a grid. A plain `Pos` can move to any neighbor;
+/// states that remember more, like the heading and how long they've kept it,
+/// decide for themselves which moves are allowed.
+pub trait SearchState: Clone + Eq + Hash {
+    fn pos(&self) -> Pos;
+
+    /// The state after moving to `pos`, one step in `direction`, or `None` if
+    /// that move isn't allowed
+    fn advance(&self, pos: Pos, direction: Direction) -> Option<Self>;
+}
+
+impl SearchState for Pos {
+    fn pos(&self) -> Pos {
+        *self
+    }
+
+    fn advance(&self, pos: Pos, _direction: Direction) -> Option<Self> {
+        Some(pos)
+    }
+}
+
+/// The states a search went through, start and goal included, and what it
+/// cost to get there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route<S> {
+    pub path: Vec<S>,
+    pub cost: usize,
+}
+
+impl<S: SearchState> Route<S> {
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.path.iter().map(S::pos)
+    }
+}
+
+impl<T> Grid<T> {
+    /// The states reachable in one step from `state`, with the cell entered
+    fn moves<'a, S: SearchState>(&'a self, state: &S) -> impl Iterator<Item = (S, &'a T)> {
+        let state = state.clone();
+        Direction::all().into_iter().filter_map(move |d| {
+            let pos = self.step(&state.pos(), d)?;
+            let next = state.advance(pos, d)?;
+            Some((next, &self[pos]))
+        })
+    }
+
+    /// The route with the fewest steps to a state matching `goal`, only
+    /// entering cells that are `passable`. The cost is the number of steps.
+    pub fn bfs<S: SearchState>(
+        &self,
+        start: S,
+        passable: impl Fn(&Pos, &T) -> bool,
+        goal: impl FnMut(&S) -> bool,
+    ) -> Option<Route<S>> {
+        let path = bfs(
+            &start,
+            |s| {
+                self.moves(s)
+                    .filter(|(next, cell)| passable(&next.pos(), cell))
+                    .map(|(next, _)| next)
+                    .collect::<Vec<_>>()
+            },
+            goal,
+        )?;
+        let cost = path.len() - 1;
+        Some(Route { path, cost })
+    }
+
+    /// The cheapest route to a state matching `goal`, where `cost` is what it
+    /// takes to enter a cell, or `None` if it can't be entered
+    pub fn dijkstra<S: SearchState>(
+        &self,
+        start: S,
+        cost: impl Fn(&Pos, &T) -> Option<usize>,
+        goal: impl FnMut(&S) -> bool,
+    ) -> Option<Route<S>> {
+        let (path, cost) = dijkstra(&start, |s| self.weighted_moves(s, &cost), goal)?;
+        Some(Route { path, cost })
+    }
+
+    /// Like `dijkstra`, but heading for `target` first. The Manhattan distance
+    /// is used as the estimate, so every cell has to cost at least 1 for the
+    /// route to be the cheapest.
+    pub fn astar<S: SearchState>(
+        &self,
+        start: S,
+        target: Pos,
+        cost: impl Fn(&Pos, &T) -> Option<usize>,
+        goal: impl FnMut(&S) -> bool,
+    ) -> Option<Route<S>> {
+        let (path, cost) = astar(
+            &start,
+            |s| self.weighted_moves(s, &cost),
+            |s| {
+                let pos = s.pos();
+                pos.x.abs_diff(target.x) + pos.y.abs_diff(target.y)
+            },
+            goal,
+        )?;
+        Some(Route { path, cost })
+    }
+
+    fn weighted_moves<S: SearchState>(
+        &self,
+        state: &S,
+        cost: &impl Fn(&Pos, &T) -> Option<usize>,
+    ) -> Vec<(S, usize)> {
+        self.moves(state)
+            .filter_map(|(next, cell)| Some((cost(&next.pos(), cell)?, next)))
+            .map(|(cost, next)| (next, cost))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        let grid = Grid::parse_with("191\n111\n#1#\n", |c| {
+            Ok::<_, &str>(c.to_digit(10).map(|d| d as usize))
+        })
+        .unwrap();
+        let end = Pos { x: 2, y: 0 };
+
+        let route = grid
+            .bfs(Pos::ZERO, |_, c| c.is_some(), |p| *p == end)
+            .unwrap();
+        assert_eq!(route.cost, 2);
+        let around = grid
+            .bfs(Pos::ZERO, |_, c| *c == Some(1), |p| *p == end)
+            .unwrap();
+        assert_eq!(around.positions().nth(2), Some(Pos { x: 1, y: 1 }));
+        assert_eq!(grid.bfs(Pos::ZERO, |_, c| c.is_some(), |p| p.y == 3), None);
+
+        // Going through the 9 is shorter, but not cheaper
+        let cost = |_: &Pos, c: &Option<usize>| *c;
+        let cheapest = grid.dijkstra(Pos::ZERO, cost, |p| *p == end).unwrap();
+        assert_eq!(cheapest.cost, 4);
+        assert_eq!(cheapest.path, around.path);
+        assert_eq!(
+            grid.astar(Pos::ZERO, end, cost, |p| *p == end),
+            Some(cheapest)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use super::GridMap;
+use crate::Point;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// A grid that only stores the cells that have been set, for when a `Grid`
+/// covering the same area would be mostly empty. Coordinates can go negative,
+/// and the bounds grow to fit whatever is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn value(&self, pos: &Point) -> Option<&T> {
+        self.cells.get(pos)
+    }
+
+    pub fn get_mut(&mut self, pos: &Point) -> Option<&mut T> {
+        self.cells.get_mut(pos)
+    }
+
+    pub fn set(&mut self, pos: &Point, value: T) {
+        self.bounds = Some(match self.bounds {
+            None => (*pos, *pos),
+            Some((min, max)) => (
+                Point::new(min.x.min(pos.x), min.y.min(pos.y)),
+                Point::new(max.x.max(pos.x), max.y.max(pos.y)),
+            ),
+        });
+        self.cells.insert(*pos, value);
+    }
+
+    /// Clear a cell. The bounds stay as they are.
+    pub fn remove(&mut self, pos: &Point) -> Option<T> {
+        self.cells.remove(pos)
+    }
+
+    /// How many cells are set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The cells that are set, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+}
+
+impl<T> GridMap for SparseGrid<T> {
+    type Coord = Point;
+    type Cell = T;
+
+    fn value(&self, pos: &Point) -> Option<&T> {
+        SparseGrid::value(self, pos)
+    }
+
+    fn set(&mut self, pos: &Point, value: T) {
+        SparseGrid::set(self, pos, value)
+    }
+
+    fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    fn contains(&self, pos: &Point) -> bool {
+        self.bounds.is_some_and(|(min, max)| {
+            (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+        })
+    }
+
+    fn scan(&self) -> impl Iterator<Item = Point> + '_ {
+        self.bounds.into_iter().flat_map(|(min, max)| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+        })
+    }
+
+    fn cells(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied().sorted()
+    }
+
+    fn render(&self, f: impl Fn(Option<&T>) -> char) -> String {
+        let mut out = String::new();
+        if let Some((min, max)) = self.bounds {
+            for y in min.y..=max.y {
+                out.extend((min.x..=max.x).map(|x| f(self.value(&Point::new(x, y)))));
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (pos, value) in iter {
+            grid.set(&pos, value);
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Connectivity, Direction, Path};
+
+    #[test]
+    fn test_sparse() {
+        // A square loop around the origin, dug out the same way as day 18
+        let mut grid = SparseGrid::new();
+        let mut pos = Point::new(-2, -2);
+        for dir in [
+            Direction::East,
+            Direction::South,
+            Direction::West,
+            Direction::North,
+        ] {
+            for _ in 0..4 {
+                grid.set(&pos, '#');
+                pos += dir.into();
+            }
+        }
+        assert_eq!(grid.len(), 16);
+        assert_eq!(grid.bounds(), Some((Point::new(-2, -2), Point::new(2, 2))));
+        assert_eq!(grid.step(&Point::new(2, 0), Direction::East), None);
+
+        let path = Path::from_grid(&grid, |g, p| g.value(p).is_some()).unwrap();
+        assert_eq!(path.0.len(), 16);
+        assert_eq!(path.0[0].pos, Point::new(-2, -2));
+
+        // Only the set cells are labelled, so the hole in the middle isn't
+        let regions = grid.regions(Connectivity::Four, |_, _| true);
+        assert_eq!(regions.regions.len(), 1);
+        assert_eq!(regions.regions[0].area, 16);
+        assert_eq!(regions.regions[0].perimeter, 32);
+        assert_eq!(regions.label(&Point::ZERO), None);
+
+        grid.flood_fill(Point::ZERO, '#', |g, p| g.value(p).is_some());
+        assert_eq!(grid.len(), 25);
+        assert_eq!(grid.render(|c| *c.unwrap_or(&'.')).lines().count(), 5);
+    }
+}
@@ -0,0 +1,95 @@
+use super::Grid;
+use crate::{Direction, Point, Pos};
+use std::ops::Index;
+
+/// A grid repeated forever in every direction. Any point maps back to a cell
+/// of the original, which is the tile at `(0, 0)`.
+pub struct Tiled<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<T> Clone for Tiled<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Tiled<'_, T> {}
+
+impl<'a, T> Tiled<'a, T> {
+    /// The grid being repeated
+    pub fn base(&self) -> &'a Grid<T> {
+        self.grid
+    }
+
+    /// The cell of the original grid that `point` is a copy of
+    pub fn wrap(&self, point: &Point) -> Pos {
+        let size = self.grid.size();
+        Pos {
+            x: point.x.rem_euclid(size.x as i64) as usize,
+            y: point.y.rem_euclid(size.y as i64) as usize,
+        }
+    }
+
+    /// Which copy of the grid `point` is in, counting copies rather than cells
+    pub fn tile(&self, point: &Point) -> Point {
+        let size = self.grid.size();
+        Point {
+            x: point.x.div_euclid(size.x as i64),
+            y: point.y.div_euclid(size.y as i64),
+        }
+    }
+
+    pub fn value(&self, point: &Point) -> &'a T {
+        &self.grid[self.wrap(point)]
+    }
+
+    /// Stepping never fails, since there's no edge to fall off
+    pub fn step(&self, point: &Point, direction: Direction) -> Point {
+        *point + direction.into()
+    }
+
+    pub fn neighbors(&self, point: &Point) -> impl Iterator<Item = Point> {
+        let point = *point;
+        Direction::all().into_iter().map(move |d| point + d.into())
+    }
+}
+
+impl<T> Index<Point> for Tiled<'_, T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.value(&point)
+    }
+}
+
+impl<T> Grid<T> {
+    /// View the grid as repeating forever in every direction
+    pub fn tiled(&self) -> Tiled<'_, T> {
+        assert!(
+            self.width > 0 && self.height > 0,
+            "can't tile an empty grid"
+        );
+        Tiled { grid: self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tiled() {
+        let grid = Grid::parse_with("12\n34\n5x\n", |c| Ok::<_, &str>(c)).unwrap();
+        let tiled = grid.tiled();
+        assert_eq!(tiled[Point::new(1, 1)], '4');
+        assert_eq!(tiled[Point::new(-1, -1)], 'x');
+        assert_eq!(tiled.wrap(&Point::new(5, -4)), Pos { x: 1, y: 2 });
+        assert_eq!(tiled.tile(&Point::new(5, -4)), Point::new(2, -2));
+        assert_eq!(tiled.tile(&Point::new(1, 2)), Point::ZERO);
+
+        let west = tiled.step(&Point::ZERO, Direction::West);
+        assert_eq!((tiled[west], tiled.tile(&west)), ('2', Point::new(-1, 0)));
+        assert_eq!(tiled.neighbors(&Point::ZERO).count(), 4);
+    }
+}
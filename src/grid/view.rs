@@ -0,0 +1,360 @@
+use super::Grid;
+use crate::Pos;
+use std::ops::{Index, IndexMut};
+
+/// A rotation or reflection of a grid: flip the view's axes, then swap them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Transform {
+    transpose: bool,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl Transform {
+    const TRANSPOSE: Self = Self::new(true, false, false);
+    const ROTATE_CW: Self = Self::new(true, true, false);
+    const ROTATE_CCW: Self = Self::new(true, false, true);
+    const FLIP_HORIZONTAL: Self = Self::new(false, true, false);
+    const FLIP_VERTICAL: Self = Self::new(false, false, true);
+
+    const fn new(transpose: bool, flip_x: bool, flip_y: bool) -> Self {
+        Self {
+            transpose,
+            flip_x,
+            flip_y,
+        }
+    }
+
+    /// Apply `next` on top of this transform
+    fn then(self, next: Self) -> Self {
+        // Our flips happen after `next` swaps the axes, so they swap too
+        let (flip_x, flip_y) = if next.transpose {
+            (self.flip_y, self.flip_x)
+        } else {
+            (self.flip_x, self.flip_y)
+        };
+        Self {
+            transpose: self.transpose ^ next.transpose,
+            flip_x: next.flip_x ^ flip_x,
+            flip_y: next.flip_y ^ flip_y,
+        }
+    }
+
+    /// Size of the view of a grid of `size`
+    fn size(&self, size: Pos) -> Pos {
+        if self.transpose {
+            Pos {
+                x: size.y,
+                y: size.x,
+            }
+        } else {
+            size
+        }
+    }
+
+    /// Position in a grid of `size` that `pos` in the view refers to
+    fn apply(&self, pos: &Pos, size: Pos) -> Option<Pos> {
+        let view = self.size(size);
+        if pos.x >= view.x || pos.y >= view.y {
+            return None;
+        }
+        let x = if self.flip_x {
+            view.x - 1 - pos.x
+        } else {
+            pos.x
+        };
+        let y = if self.flip_y {
+            view.y - 1 - pos.y
+        } else {
+            pos.y
+        };
+        Some(if self.transpose {
+            Pos { x: y, y: x }
+        } else {
+            Pos { x, y }
+        })
+    }
+}
+
+/// A rotated or flipped grid that reads from the original without copying it
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    transform: Transform,
+}
+
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn size(&self) -> Pos {
+        self.transform.size(self.grid.size())
+    }
+
+    pub fn value(&self, pos: &Pos) -> Option<&'a T> {
+        self.grid
+            .value(&self.transform.apply(pos, self.grid.size())?)
+    }
+
+    /// Like indexing, but borrowing from the grid rather than the view
+    fn at(&self, pos: Pos) -> &'a T {
+        self.value(&pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a view of {:?}", self.size()))
+    }
+
+    pub fn scan(&self) -> impl Iterator<Item = Pos> {
+        let size = self.size();
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| Pos { x, y }))
+    }
+
+    pub fn iter_with_pos(&self) -> impl Iterator<Item = (Pos, &'a T)> {
+        let view = *self;
+        self.scan().map(move |pos| (pos, view.at(pos)))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+        let view = *self;
+        (0..self.size().y).map(move |y| view.row(y))
+    }
+
+    pub fn row(&self, idx: usize) -> impl Iterator<Item = &'a T> {
+        let view = *self;
+        (0..self.size().x).map(move |x| view.at(Pos { x, y: idx }))
+    }
+
+    pub fn col(&self, idx: usize) -> impl Iterator<Item = &'a T> {
+        let view = *self;
+        (0..self.size().y).map(move |y| view.at(Pos { x: idx, y }))
+    }
+
+    pub fn transposed(self) -> Self {
+        self.then(Transform::TRANSPOSE)
+    }
+
+    pub fn rotated_cw(self) -> Self {
+        self.then(Transform::ROTATE_CW)
+    }
+
+    pub fn rotated_ccw(self) -> Self {
+        self.then(Transform::ROTATE_CCW)
+    }
+
+    pub fn flipped_horizontal(self) -> Self {
+        self.then(Transform::FLIP_HORIZONTAL)
+    }
+
+    pub fn flipped_vertical(self) -> Self {
+        self.then(Transform::FLIP_VERTICAL)
+    }
+
+    fn then(self, next: Transform) -> Self {
+        Self {
+            transform: self.transform.then(next),
+            ..self
+        }
+    }
+
+    /// Copy the view into a grid of its own
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let size = self.size();
+        Grid {
+            data: self.iter_with_pos().map(|(_, t)| t.clone()).collect(),
+            width: size.x,
+            height: size.y,
+        }
+    }
+}
+
+impl<T> Index<Pos> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.at(pos)
+    }
+}
+
+/// A rotated or flipped grid that writes through to the original, so an
+/// algorithm written for one direction can run in any of them
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    transform: Transform,
+}
+
+impl<T> GridViewMut<'_, T> {
+    pub fn size(&self) -> Pos {
+        self.transform.size(self.grid.size())
+    }
+
+    pub fn value(&self, pos: &Pos) -> Option<&T> {
+        self.grid
+            .value(&self.transform.apply(pos, self.grid.size())?)
+    }
+
+    pub fn get_mut(&mut self, pos: &Pos) -> Option<&mut T> {
+        let pos = self.transform.apply(pos, self.grid.size())?;
+        self.grid.get_mut(&pos)
+    }
+
+    pub fn set(&mut self, pos: &Pos, value: T) {
+        self[*pos] = value;
+    }
+
+    pub fn transposed(self) -> Self {
+        self.then(Transform::TRANSPOSE)
+    }
+
+    pub fn rotated_cw(self) -> Self {
+        self.then(Transform::ROTATE_CW)
+    }
+
+    pub fn rotated_ccw(self) -> Self {
+        self.then(Transform::ROTATE_CCW)
+    }
+
+    pub fn flipped_horizontal(self) -> Self {
+        self.then(Transform::FLIP_HORIZONTAL)
+    }
+
+    pub fn flipped_vertical(self) -> Self {
+        self.then(Transform::FLIP_VERTICAL)
+    }
+
+    fn then(self, next: Transform) -> Self {
+        Self {
+            transform: self.transform.then(next),
+            ..self
+        }
+    }
+}
+
+impl<T> Index<Pos> for GridViewMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.value(&pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a view of {:?}", self.size()))
+    }
+}
+
+impl<T> IndexMut<Pos> for GridViewMut<'_, T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let size = self.size();
+        self.get_mut(&pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a view of {size:?}"))
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self,
+            transform: Transform::default(),
+        }
+    }
+
+    pub fn view_mut(&mut self) -> GridViewMut<'_, T> {
+        GridViewMut {
+            grid: self,
+            transform: Transform::default(),
+        }
+    }
+
+    pub fn transposed(&self) -> GridView<'_, T> {
+        self.view().transposed()
+    }
+
+    pub fn rotated_cw(&self) -> GridView<'_, T> {
+        self.view().rotated_cw()
+    }
+
+    pub fn rotated_ccw(&self) -> GridView<'_, T> {
+        self.view().rotated_ccw()
+    }
+
+    pub fn flipped_horizontal(&self) -> GridView<'_, T> {
+        self.view().flipped_horizontal()
+    }
+
+    pub fn flipped_vertical(&self) -> GridView<'_, T> {
+        self.view().flipped_vertical()
+    }
+
+    /// Mirror the grid left to right
+    pub fn flip_horizontal(&mut self) {
+        for y in 0..self.height {
+            self.row_slice_mut(y).reverse();
+        }
+    }
+
+    /// Mirror the grid top to bottom
+    pub fn flip_vertical(&mut self) {
+        self.data.reverse();
+        self.flip_horizontal();
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Clone,
+{
+    pub fn transpose(&mut self) {
+        *self = self.transposed().to_grid();
+    }
+
+    pub fn rotate_cw(&mut self) {
+        *self = self.rotated_cw().to_grid();
+    }
+
+    pub fn rotate_ccw(&mut self) {
+        *self = self.rotated_ccw().to_grid();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+
+    fn rows<T: Copy>(view: GridView<T>) -> Vec<Vec<T>> {
+        view.rows().map(|r| r.copied().collect()).collect()
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::parse_with("123\n456\n", |c| c.to_digit(10).ok_or("")).unwrap();
+        assert_eq!(rows(grid.transposed()), [[1, 4], [2, 5], [3, 6]]);
+        assert_eq!(rows(grid.rotated_cw()), [[4, 1], [5, 2], [6, 3]]);
+        assert_eq!(rows(grid.rotated_ccw()), [[3, 6], [2, 5], [1, 4]]);
+        assert_eq!(rows(grid.flipped_horizontal()), [[3, 2, 1], [6, 5, 4]]);
+        assert_eq!(rows(grid.flipped_vertical()), [[4, 5, 6], [1, 2, 3]]);
+
+        // Views compose, and match the in-place versions
+        let turned = grid.rotated_cw().rotated_cw();
+        assert_eq!(rows(turned), [[6, 5, 4], [3, 2, 1]]);
+        assert_eq!(rows(grid.rotated_cw().rotated_ccw()), rows(grid.view()));
+        assert_eq!(
+            rows(grid.flipped_vertical().transposed()),
+            rows(grid.rotated_cw())
+        );
+        let mut copy = grid.clone();
+        copy.rotate_cw();
+        copy.rotate_cw();
+        assert_eq!(copy, turned.to_grid());
+        copy.flip_horizontal();
+        copy.flip_vertical();
+        assert_eq!(copy, grid);
+        assert_eq!(turned.col(2).copied().collect_vec(), [4, 1]);
+
+        let mut grid = grid;
+        let mut view = grid.view_mut().rotated_ccw();
+        view[Pos { x: 0, y: 0 }] = 0;
+        assert_eq!(grid.row_slice(0), [1, 2, 0]);
+    }
+}
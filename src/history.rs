@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Something that happened while working on a puzzle. History is kept as one
+/// JSON object per line, so it can be appended to without reading it first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Entry {
+    /// A solution was run locally
+    Run {
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: String,
+        /// Wall time of the solution, in microseconds
+        micros: u64,
+        at: i64,
+    },
+    /// An answer was submitted
+    Submission {
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: String,
+        outcome: Outcome,
+        at: i64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    Wait,
+    WrongLevel,
+}
+
+impl Entry {
+    pub fn run(year: u16, day: u8, part: u8, answer: &str, elapsed: Duration) -> Self {
+        Self::Run {
+            year,
+            day,
+            part,
+            answer: answer.to_string(),
+            micros: elapsed.as_micros() as u64,
+            at: now(),
+        }
+    }
+
+    pub fn submission(year: u16, day: u8, part: u8, answer: &str, outcome: Outcome) -> Self {
+        Self::Submission {
+            year,
+            day,
+            part,
+            answer: answer.to_string(),
+            outcome,
+            at: now(),
+        }
+    }
+
+    pub fn year(&self) -> u16 {
+        match self {
+            Self::Run { year, .. } | Self::Submission { year, .. } => *year,
+        }
+    }
+}
+
+impl From<&aoc_client::SubmissionOutcome> for Outcome {
+    fn from(value: &aoc_client::SubmissionOutcome) -> Self {
+        use aoc_client::SubmissionOutcome as O;
+        match value {
+            O::Correct => Self::Correct,
+            O::Incorrect => Self::Incorrect,
+            O::Wait => Self::Wait,
+            O::WrongLevel => Self::WrongLevel,
+        }
+    }
+}
+
+/// Append an entry to the history file. Failing to record history shouldn't
+/// stop anything else, so errors are only logged.
+pub fn record(path: &Path, entry: &Entry) {
+    let write = || -> Result<(), anyhow::Error> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    };
+    if let Err(e) = write() {
+        log::warn!("failed to record history to {}: {e}", path.display());
+    }
+}
+
+/// Read all recorded history. A missing file is an empty history.
+pub fn load(path: &Path) -> Result<Vec<Entry>, anyhow::Error> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    std::fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| anyhow::anyhow!("{} line {}: {e}", path.display(), idx + 1))
+        })
+        .collect()
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
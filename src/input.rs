@@ -0,0 +1,150 @@
+//! Loading puzzle input. Everything that reaches a solution goes through here,
+//! so solutions can assume `\n` line endings and exactly one trailing newline.
+
+use std::path::{Path, PathBuf};
+
+/// Responses AoC sends instead of an input
+const ERROR_BODIES: &[(&str, &str)] = &[
+    (
+        "Please log in",
+        "not logged in; the session cookie is missing or expired",
+    ),
+    ("before it unlocks", "the puzzle hasn't unlocked yet"),
+    ("404 Not Found", "no input for this day"),
+];
+
+/// Convert line endings to `\n`, drop any byte order mark, and make sure the
+/// input ends in exactly one newline.
+pub fn normalize(raw: &str) -> String {
+    let text = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let mut text = text.replace("\r\n", "\n").replace('\r', "\n");
+    text.truncate(text.trim_end_matches('\n').len());
+    text.push('\n');
+    text
+}
+
+/// Check that `raw` looks like a real puzzle input, and not an error page or a
+/// partial download.
+pub fn validate(raw: &str) -> Result<(), anyhow::Error> {
+    let trimmed = raw.trim_start();
+    if trimmed.is_empty() {
+        anyhow::bail!("input is empty");
+    }
+    for (needle, reason) in ERROR_BODIES {
+        if raw.contains(needle) {
+            anyhow::bail!("input is an error message: {reason}");
+        }
+    }
+    let start = trimmed[..trimmed.len().min(64)].to_ascii_lowercase();
+    if start.starts_with("<!doctype") || start.starts_with("<html") {
+        anyhow::bail!("input is an HTML page, not a puzzle input");
+    }
+    // Every AoC input ends with a newline
+    if !raw.ends_with('\n') {
+        anyhow::bail!("input doesn't end with a newline; the download may be truncated");
+    }
+    Ok(())
+}
+
+/// FNV-1a hash of the input. Stable across builds and platforms, unlike
+/// `DefaultHasher`, so it can be written to disk.
+pub fn content_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Where the hash of an input file is recorded
+fn hash_path(input_path: &Path) -> PathBuf {
+    let mut name = input_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".hash");
+    input_path.with_file_name(name)
+}
+
+/// Save a freshly downloaded input. Fails, without writing anything, if the
+/// download isn't a real input.
+pub fn save(path: &Path, raw: &str) -> Result<(), anyhow::Error> {
+    validate(raw).map_err(|e| anyhow::anyhow!("refusing to save {}: {e}", path.display()))?;
+
+    let text = normalize(raw);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, &text)?;
+    std::fs::write(hash_path(path), content_hash(&text))?;
+    Ok(())
+}
+
+/// Read a cached input. Problems with the file are warnings rather than errors,
+/// since it might have been edited on purpose.
+pub fn load(path: &Path) -> Result<String, anyhow::Error> {
+    let raw = std::fs::read_to_string(path)?;
+    if let Err(e) = validate(&raw) {
+        log::warn!("{}: {e}", path.display());
+    }
+
+    let text = normalize(&raw);
+    let hash = content_hash(&text);
+    let hash_path = hash_path(path);
+    match std::fs::read_to_string(&hash_path) {
+        Ok(recorded) if recorded.trim() == hash => {}
+        Ok(recorded) => {
+            log::warn!(
+                "{} has changed since it was downloaded (hash {} is now {hash})",
+                path.display(),
+                recorded.trim()
+            );
+            std::fs::write(&hash_path, &hash)?;
+        }
+        Err(_) => std::fs::write(&hash_path, &hash)?,
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(normalize("\u{feff}a\nb"), "a\nb\n");
+        assert_eq!(normalize("a\n\nb\n\n\n"), "a\n\nb\n");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("1 2 3\n4 5 6\n").is_ok());
+        assert!(validate("").is_err());
+        assert!(validate("1 2 3\n4 5").is_err());
+        assert!(validate("<!DOCTYPE html>\n<html></html>\n").is_err());
+        let login = "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+        assert!(validate(login)
+            .unwrap_err()
+            .to_string()
+            .contains("not logged in"));
+    }
+
+    #[test]
+    fn test_load_detects_changes() {
+        let dir = std::env::temp_dir().join(format!("advent2023-input-{}", std::process::id()));
+        let path = dir.join("day01.txt");
+
+        assert!(save(&path, "Please log in").is_err());
+        assert!(!path.exists());
+
+        save(&path, "1\r\n2\r\n").unwrap();
+        assert_eq!(load(&path).unwrap(), "1\n2\n");
+        let hash = std::fs::read_to_string(hash_path(&path)).unwrap();
+        assert_eq!(hash, content_hash("1\n2\n"));
+
+        std::fs::write(&path, "3\n").unwrap();
+        assert_eq!(load(&path).unwrap(), "3\n");
+        let hash = std::fs::read_to_string(hash_path(&path)).unwrap();
+        assert_eq!(hash, content_hash("3\n"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{client::Client, RootOpt};
+use clap::Parser;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// AoC asks that leaderboards are fetched no more than once every 15 minutes
+const CACHE_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Parser, Debug, Clone)]
+pub struct LeaderboardCommand {
+    /// Leaderboard id (the number at the end of the leaderboard's URL)
+    pub id: u32,
+
+    /// Ignore the cache and download the leaderboard again
+    #[arg(long)]
+    pub refresh: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    pub members: HashMap<u64, Member>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    pub last_star_ts: i64,
+    /// day -> part -> star
+    pub completion_day_level: HashMap<u32, HashMap<u32, Star>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> Result<Self, anyhow::Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Members ordered by score. Ties go to whoever got their last star first.
+    pub fn ranked(&self) -> Vec<&Member> {
+        self.members
+            .values()
+            .sorted_by_key(|m| (std::cmp::Reverse(m.local_score), m.last_star_ts, m.id))
+            .collect_vec()
+    }
+
+    pub fn render(&self) -> Result<String, anyhow::Error> {
+        let year: i32 = self.event.parse()?;
+        let ranked = self.ranked();
+        let name_width = ranked.iter().map(|m| m.name().len()).max().unwrap_or(0);
+        let mut out = String::new();
+
+        let owner = self
+            .members
+            .get(&self.owner_id)
+            .map(|m| m.name())
+            .unwrap_or_default();
+        writeln!(out, "Private leaderboard of {owner} for {}", self.event)?;
+        writeln!(out)?;
+        writeln!(out, "  #  Score  Stars  Name")?;
+        for (rank, member) in ranked.iter().enumerate() {
+            writeln!(
+                out,
+                "{:>3}  {:>5}  {:>5}  {}",
+                rank + 1,
+                member.local_score,
+                member.stars,
+                member.name()
+            )?;
+        }
+
+        let days = ranked
+            .iter()
+            .flat_map(|m| m.completion_day_level.keys().copied())
+            .unique()
+            .sorted();
+        for day in days {
+            let unlock = unlock_ts(year, day)?;
+            writeln!(out)?;
+            writeln!(out, "Day {day}")?;
+            writeln!(
+                out,
+                "  {:name_width$}  {:>11}  {:>11}  {:>11}",
+                "Name", "Part 1", "Part 2", "Delta"
+            )?;
+
+            // Fastest to finish both parts first, then whoever only got one star
+            let finishers = ranked
+                .iter()
+                .filter_map(|m| {
+                    let stars = m.completion_day_level.get(&day)?;
+                    let one = stars.get(&1)?.get_star_ts;
+                    let two = stars.get(&2).map(|s| s.get_star_ts);
+                    Some((m, one, two))
+                })
+                .sorted_by_key(|(_, one, two)| (two.unwrap_or(i64::MAX), *one));
+            for (member, one, two) in finishers {
+                let show = |ts: Option<i64>| ts.map(format_duration).unwrap_or("-".to_string());
+                writeln!(
+                    out,
+                    "  {:name_width$}  {:>11}  {:>11}  {:>11}",
+                    member.name(),
+                    show(Some(one - unlock)),
+                    show(two.map(|t| t - unlock)),
+                    show(two.map(|t| t - one)),
+                )?;
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+impl Member {
+    pub fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+}
+
+/// Unix timestamp of when a puzzle unlocks: midnight, US Eastern
+pub(crate) fn unlock_ts(year: i32, day: u32) -> Result<i64, anyhow::Error> {
+    let unlock = chrono::NaiveDate::from_ymd_opt(year, 12, day)
+        .and_then(|d| d.and_hms_opt(5, 0, 0))
+        .ok_or_else(|| anyhow::anyhow!("invalid puzzle date: {year} day {day}"))?;
+    Ok(unlock.and_utc().timestamp())
+}
+
+/// Format seconds as `hh:mm:ss`, with a day count if needed
+pub(crate) fn format_duration(secs: i64) -> String {
+    let (days, secs) = (secs / 86400, secs % 86400);
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    );
+    if days > 0 {
+        format!("{days}d {time}")
+    } else {
+        time
+    }
+}
+
+fn cache_path(dir: &Path, year: u16, id: u32) -> PathBuf {
+    dir.join(format!("leaderboard_{year}_{id}.json"))
+}
+
+/// Cached leaderboard JSON, if there is any and it isn't too old
+fn read_cache(path: &Path, max_age: Duration) -> Option<String> {
+    let age = path.metadata().ok()?.modified().ok()?.elapsed().ok()?;
+    if age > max_age {
+        log::debug!("{} is stale ({}s old)", path.display(), age.as_secs());
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+fn write_cache(path: &Path, json: &str) -> Result<(), anyhow::Error> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+impl LeaderboardCommand {
+    pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
+        log::info!("Running leaderboard command");
+
+        let config = &opt.config;
+        let path = cache_path(&config.cache_dir, config.year, self.id);
+        let cached = match self.refresh {
+            true => None,
+            false => read_cache(&path, CACHE_MAX_AGE),
+        };
+        let json = match cached {
+            Some(json) => {
+                log::info!("Using cached leaderboard {}", path.display());
+                json
+            }
+            None => {
+                let json = Client::new(opt)?.get_private_leaderboard(self.id)?;
+                // Make sure it parses before it goes in the cache
+                Leaderboard::parse(&json)?;
+                write_cache(&path, &json)?;
+                json
+            }
+        };
+
+        print!("{}", Leaderboard::parse(&json)?.render()?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../fixtures/leaderboard.json");
+
+    #[test]
+    fn test_ranking() {
+        let board = Leaderboard::parse(FIXTURE).unwrap();
+        let names = board.ranked().iter().map(|m| m.name()).collect_vec();
+        assert_eq!(names, ["Alice", "(anonymous user #1003)", "Bob"]);
+    }
+
+    #[test]
+    fn test_render() {
+        let board = Leaderboard::parse(FIXTURE).unwrap();
+        let out = board.render().unwrap();
+        let lines = out.lines().collect_vec();
+        assert_eq!(
+            lines[3].split_whitespace().collect_vec(),
+            ["1", "11", "4", "Alice"]
+        );
+
+        let day_one = lines.iter().position(|l| *l == "Day 1").unwrap();
+        let row = |idx: usize| lines[idx].split_whitespace().collect_vec();
+        assert_eq!(
+            row(day_one + 2),
+            [
+                "(anonymous",
+                "user",
+                "#1003)",
+                "00:01:40",
+                "00:03:20",
+                "00:01:40"
+            ]
+        );
+        assert_eq!(
+            row(day_one + 3),
+            ["Alice", "00:04:12", "00:09:47", "00:05:35"]
+        );
+        assert_eq!(row(day_one + 4), ["Bob", "00:12:01", "-", "-"]);
+
+        let day_two = lines.iter().position(|l| *l == "Day 2").unwrap();
+        assert_eq!(row(day_two + 3), ["Bob", "1d", "01:00:00", "-", "-"]);
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = std::env::temp_dir().join(format!("advent2023-lb-{}", std::process::id()));
+        let path = cache_path(&dir, 2023, 1234);
+        assert_eq!(read_cache(&path, CACHE_MAX_AGE), None);
+
+        write_cache(&path, FIXTURE).unwrap();
+        assert_eq!(read_cache(&path, CACHE_MAX_AGE).as_deref(), Some(FIXTURE));
+        assert_eq!(read_cache(&path, Duration::ZERO), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,32 +1,55 @@
+mod auth;
 mod client;
+mod config;
+pub mod cycle;
+mod history;
+mod input;
+mod leaderboard;
+mod list;
+pub mod memo;
+pub mod parse;
 mod puzzle;
+mod stats;
 
-pub use puzzle::Puzzle;
+pub use config::{Config, OutputFormat};
+pub use puzzle::{solution, solutions, Puzzle, PuzzleInfo, Solution};
 
 mod direction;
 mod grid;
 mod path;
+mod point;
 mod pos;
+mod tile;
 
-pub use direction::Direction;
-pub use grid::Grid;
+pub use direction::{Direction, Direction8};
+pub use grid::{
+    BitGrid, Connectivity, Coord, Grid, GridError, GridMap, GridView, GridViewMut, Region, Regions,
+    Route, SearchState, SparseGrid, Tiled,
+};
 pub use path::Path;
+pub use point::Point;
 pub use pos::Pos;
+pub use tile::{parse_tiles, GridTile, TileError};
 
+use auth::AuthCommand;
 use clap::Parser;
 use client::DownloadCommand;
 use client::SubmitCommand;
+use leaderboard::LeaderboardCommand;
+use list::ListCommand;
 use puzzle::PuzzleCommand;
+use stats::StatsCommand;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug, Clone)]
 pub struct RootOpt {
-    /// Year to run (default: 2023)
-    #[arg(short, long, default_value_t = 2023)]
-    pub year: u16,
+    /// Year to run (default: 2023, or `year` from the config file)
+    #[arg(short, long)]
+    pub year: Option<u16>,
 
     /// Day to run
     #[arg(short, long)]
-    pub day: u8,
+    pub day: Option<u8>,
 
     /// Part to run
     #[arg(short, long, default_value_t = 1)]
@@ -36,6 +59,34 @@ pub struct RootOpt {
     #[arg(long)]
     pub data: bool,
 
+    /// Project config file (default: ./advent.toml)
+    #[arg(long = "config")]
+    pub config_path: Option<PathBuf>,
+
+    /// Directory holding puzzle inputs
+    #[arg(long)]
+    pub input_dir: Option<PathBuf>,
+
+    /// Directory holding puzzle descriptions
+    #[arg(long)]
+    pub assignment_dir: Option<PathBuf>,
+
+    /// Directory for cached downloads
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// File containing the session cookie
+    #[arg(long)]
+    pub session_file: Option<PathBuf>,
+
+    /// How to print results
+    #[arg(long, value_enum)]
+    pub output: Option<OutputFormat>,
+
+    /// Settings from the config file, with command line overrides applied
+    #[arg(skip)]
+    pub config: Config,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -45,12 +96,45 @@ enum Commands {
     Puzzle(puzzle::PuzzleCommand),
     Download(DownloadCommand),
     Submit(SubmitCommand),
+    Auth(AuthCommand),
+    Leaderboard(LeaderboardCommand),
+    Stats(StatsCommand),
+    List(ListCommand),
 }
 
 impl RootOpt {
-    pub fn run(&self) -> Result<(), anyhow::Error> {
-        log::info!("Running day {} part {}", self.day, self.part);
+    /// Load the project config file and apply any command line overrides to it
+    pub fn load_config(mut self) -> Result<Self, anyhow::Error> {
+        let mut config = Config::load(self.config_path.as_deref())?;
+        if let Some(year) = self.year {
+            config.year = year;
+        }
+        if let Some(dir) = &self.input_dir {
+            config.input_dir = dir.clone();
+        }
+        if let Some(dir) = &self.assignment_dir {
+            config.assignment_dir = dir.clone();
+        }
+        if let Some(dir) = &self.cache_dir {
+            config.cache_dir = dir.clone();
+        }
+        if let Some(file) = &self.session_file {
+            config.session_file = Some(file.clone());
+        }
+        if let Some(output) = self.output {
+            config.output = output;
+        }
+        self.config = config;
+        Ok(self)
+    }
 
+    /// The day to run. Only required by commands that work on a single puzzle.
+    pub fn day(&self) -> Result<u8, anyhow::Error> {
+        self.day
+            .ok_or_else(|| anyhow::anyhow!("no day specified (use --day)"))
+    }
+
+    pub fn run(&self) -> Result<(), anyhow::Error> {
         if let Some(cmd) = &self.command {
             return cmd.run(self);
         }
@@ -67,6 +151,10 @@ impl Commands {
             Commands::Download(cmd) => cmd.run(opt),
             Commands::Submit(cmd) => cmd.run(opt),
             Commands::Puzzle(cmd) => cmd.run(opt),
+            Commands::Auth(cmd) => cmd.run(opt),
+            Commands::Leaderboard(cmd) => cmd.run(opt),
+            Commands::Stats(cmd) => cmd.run(opt),
+            Commands::List(cmd) => cmd.run(opt),
         }
     }
 }
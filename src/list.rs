@@ -0,0 +1,92 @@
+use crate::puzzle::{solution, solutions, Solution};
+use crate::{OutputFormat, RootOpt};
+use clap::Parser;
+use itertools::Itertools;
+
+#[derive(Parser, Debug, Clone)]
+pub struct ListCommand {
+    /// Only list solutions with this tag
+    #[arg(short, long)]
+    pub tag: Option<String>,
+}
+
+impl ListCommand {
+    pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
+        log::info!("Running list command");
+
+        let selected = match opt.day {
+            Some(day) => {
+                let found = solution(day);
+                vec![found.ok_or_else(|| anyhow::anyhow!("No solution found for day {day}"))?]
+            }
+            None => solutions().iter().collect_vec(),
+        };
+        let selected = selected
+            .into_iter()
+            .filter(|s| self.tag.as_deref().is_none_or(|tag| s.has_tag(tag)))
+            .collect_vec();
+
+        match opt.config.output {
+            OutputFormat::Text => print!("{}", render(&selected)),
+            OutputFormat::Json => {
+                let json = selected
+                    .iter()
+                    .map(|s| {
+                        serde_json::json!({
+                            "day": s.info.day,
+                            "title": s.info.title,
+                            "tags": s.info.tags,
+                            "parts": s.info.parts,
+                        })
+                    })
+                    .collect_vec();
+                println!("{}", serde_json::Value::Array(json));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn render(solutions: &[&Solution]) -> String {
+    let title_width = solutions
+        .iter()
+        .map(|s| s.info.title.len())
+        .max()
+        .unwrap_or(0)
+        .max("Title".len());
+
+    let mut out = format!("Day  {:title_width$}  Parts  Tags\n", "Title");
+    for s in solutions {
+        out += &format!(
+            "{:>3}  {:title_width$}  {:5}  {}\n",
+            s.info.day,
+            s.info.title,
+            s.info.parts.iter().join(","),
+            s.info.tags.join(", ")
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let days = solutions().iter().map(|s| s.info.day).collect_vec();
+        assert!(days.windows(2).all(|w| w[0] < w[1]));
+        assert!(!days.contains(&0));
+
+        let day = solution(1).unwrap();
+        assert_eq!(day.info.title, "Trebuchet?!");
+        assert!(solution(26).is_none());
+
+        let rendered = render(&[day]);
+        assert!(rendered
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("  1  Trebuchet?!"));
+    }
+}
@@ -3,6 +3,6 @@ use clap::Parser;
 
 fn main() -> Result<(), anyhow::Error> {
     env_logger::init();
-    let opt = RootOpt::parse();
+    let opt = RootOpt::parse().load_config()?;
     opt.run()
 }
@@ -0,0 +1,161 @@
+//! Support for `#[memoize]`, which caches the results of a recursive function:
+//!
+//! ```ignore
+//! #[memoize(key = (states.len(), runs.len()), key_type = (usize, usize))]
+//! fn arrangements(states: &[State], runs: &[usize]) -> usize { ... }
+//! ```
+//!
+//! By default the key is an owned copy of every argument (`&[T]` becomes
+//! `Vec<T>`, `&str` becomes `String`, and `&T` is cloned). `key`/`key_type`
+//! project the arguments to something cheaper instead.
+//!
+//! The cache lives from the outermost call until it returns, so the key only
+//! has to tell apart the calls made while solving one thing. Above, lengths
+//! are enough because every call gets a suffix of the same two slices. Each
+//! thread has its own cache, which makes memoized functions fine to call from
+//! rayon. Hit and miss counts are logged at trace level.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::thread::LocalKey;
+
+pub use macros::memoize;
+
+pub struct Cache<K, V> {
+    name: &'static str,
+    map: HashMap<K, V>,
+    /// How many calls deep we are in the function
+    depth: usize,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K, V> Cache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            map: HashMap::new(),
+            depth: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.map.get(key).cloned();
+        if value.is_some() {
+            self.hits += 1;
+        }
+        value
+    }
+
+    /// Called before running the function on a key that isn't cached. The
+    /// call lasts until the returned guard is dropped, even if the function
+    /// panics.
+    pub fn enter(cache: &'static LocalKey<RefCell<Self>>) -> Call<K, V> {
+        cache.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.misses += 1;
+            cache.depth += 1;
+        });
+        Call { cache }
+    }
+
+    /// Called with the function's result, before its `Call` is dropped
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.insert(key, value);
+    }
+}
+
+impl<K, V> Cache<K, V> {
+    /// Once the outermost call returns, the cache is reported and cleared.
+    fn exit(&mut self) {
+        self.depth -= 1;
+        if self.depth == 0 {
+            log::trace!(
+                "memoize {}: {} hits, {} misses, {} entries",
+                self.name,
+                self.hits,
+                self.misses,
+                self.map.len()
+            );
+            self.map.clear();
+            self.hits = 0;
+            self.misses = 0;
+        }
+    }
+}
+
+/// One call of a memoized function, from [`Cache::enter`] until it's dropped
+pub struct Call<K: 'static, V: 'static> {
+    cache: &'static LocalKey<RefCell<Cache<K, V>>>,
+}
+
+impl<K, V> Drop for Call<K, V> {
+    fn drop(&mut self) {
+        // The thread's cache may already be gone if it's shutting down
+        let _ = self.cache.try_with(|cache| cache.borrow_mut().exit());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[memoize]
+    fn fib(n: u64) -> u64 {
+        match n {
+            0 | 1 => n,
+            n => fib(n - 1) + fib(n - 2),
+        }
+    }
+
+    /// Ways to make `total` from `coins`
+    #[memoize(key = (coins.len(), total), key_type = (usize, u32))]
+    fn change(coins: &[u32], total: u32) -> u64 {
+        match coins.split_first() {
+            _ if total == 0 => 1,
+            None => 0,
+            Some((&coin, rest)) => {
+                let with = match total.checked_sub(coin) {
+                    Some(left) => change(coins, left),
+                    None => 0,
+                };
+                with + change(rest, total)
+            }
+        }
+    }
+
+    thread_local! {
+        static OFFSET: std::cell::Cell<Option<u64>> = const { std::cell::Cell::new(None) };
+    }
+
+    /// `n` plus the offset, panicking if there isn't one
+    #[memoize]
+    fn offset(n: u64) -> u64 {
+        match n {
+            0 => OFFSET.get().expect("no offset"),
+            n => offset(n - 1) + 1,
+        }
+    }
+
+    #[test]
+    fn test_memoize() {
+        assert_eq!(fib(90), 2880067194370816120);
+        assert_eq!(change(&[1, 2, 5], 5), 4);
+        // The cache from the last call mustn't leak into this one
+        assert_eq!(change(&[2, 3], 5), 1);
+
+        // Nor from one that panicked
+        assert!(std::panic::catch_unwind(|| offset(3)).is_err());
+        OFFSET.set(Some(10));
+        assert_eq!(offset(3), 13);
+        OFFSET.set(Some(20));
+        assert_eq!(offset(3), 23);
+    }
+}
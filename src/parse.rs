@@ -0,0 +1,364 @@
+//! Parsing puzzle input with nom. Most types get a parser from
+//! `#[derive(Format)]`, which reads a line like `Game 1: 3 blue, 4 red` with
+//! `#[format("Game {num}: {cubes:, }")]`:
+//!
+//! - `{field}` parses the field with its `Parse` impl
+//! - `{field:sep}` parses a `Vec` of one or more items, separated by `sep`
+//! - `{{` and `}}` are literal braces
+//!
+//! On an enum, each variant gets its own `#[format]`, and they're tried in
+//! order. The derive also implements `FromStr`.
+//!
+//! Whole inputs are put together from the combinators here, and run with
+//! `parse_all`:
+//!
+//! ```ignore
+//! // seeds: 79 14 55 13
+//! //
+//! // seed-to-soil map:
+//! // 50 98 2
+//! let (seeds, maps) = parse_all(input, separated_pair(
+//!     section(numbers::<u64>),
+//!     tag("\n\n"),
+//!     blocks(section(lines(numbers::<u64>))),
+//! ))?;
+//! ```
+//!
+//! Lists are strict: once a separator is seen, the next item has to parse, so
+//! errors point at the bad item rather than wherever the list gave up.
+
+use nom::error::{ContextError, ErrorKind, ParseError as _, VerboseError, VerboseErrorKind};
+
+pub use macros::Format;
+
+pub type PResult<'a, T> = nom::IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// A type that can be parsed from the start of a string
+pub trait Parse: Sized {
+    fn parser(input: &str) -> PResult<'_, Self>;
+}
+
+macro_rules! parse_int {
+    ($($t:ty),*) => {
+        $(impl Parse for $t {
+            fn parser(input: &str) -> PResult<'_, Self> {
+                nom::combinator::map_res(
+                    nom::combinator::recognize(nom::sequence::pair(
+                        nom::combinator::opt(nom::character::complete::char('-')),
+                        nom::character::complete::digit1,
+                    )),
+                    str::parse,
+                )(input)
+            }
+        })*
+    };
+}
+
+parse_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A word of letters and digits
+impl Parse for String {
+    fn parser(input: &str) -> PResult<'_, Self> {
+        nom::combinator::map(nom::character::complete::alphanumeric1, str::to_string)(input)
+    }
+}
+
+impl Parse for char {
+    fn parser(input: &str) -> PResult<'_, Self> {
+        nom::character::complete::anychar(input)
+    }
+}
+
+/// One or more `T`s with nothing in between, like `LRLRR`
+impl<T: Parse> Parse for Vec<T> {
+    fn parser(input: &str) -> PResult<'_, Self> {
+        nom::multi::many1(T::parser)(input)
+    }
+}
+
+/// Exactly `text`
+pub fn literal<'a>(
+    text: &'static str,
+    expected: &'static str,
+) -> impl FnMut(&'a str) -> PResult<'a, &'a str> {
+    nom::error::context(expected, nom::bytes::complete::tag(text))
+}
+
+/// Run `parser`, describing what was `expected` if it fails
+pub fn field<'a, T>(
+    expected: &'static str,
+    parser: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, T> {
+    nom::error::context(expected, parser)
+}
+
+/// One or more `T`s separated by `sep`
+pub fn list<'a, T: Parse>(sep: &'static str) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated(nom::bytes::complete::tag(sep), T::parser)
+}
+
+/// One or more `item`s separated by `sep`. The list ends when there's no
+/// separator, or the separator is followed by a blank line or the end of the
+/// input; anything else after a separator has to be an item.
+pub fn separated<'a, T, S>(
+    mut sep: impl FnMut(&'a str) -> PResult<'a, S>,
+    mut item: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    move |input| {
+        let (mut input, first) = item(input)?;
+        let mut items = vec![first];
+        loop {
+            let Ok((next, _)) = sep(input) else {
+                return Ok((input, items));
+            };
+            if next.is_empty() || next.starts_with('\n') {
+                return Ok((input, items));
+            }
+            let (rest, value) = item(next)?;
+            items.push(value);
+            input = rest;
+        }
+    }
+}
+
+/// Numbers on one line, separated by spaces or tabs: `79 14 -55 13`
+pub fn numbers<T: Parse>(input: &str) -> PResult<'_, Vec<T>> {
+    separated(nom::character::complete::space1, T::parser)(input)
+}
+
+/// `item`s separated by commas, with optional spaces after each comma
+pub fn comma_list<'a, T>(
+    item: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated(
+        nom::sequence::pair(
+            nom::character::complete::char(','),
+            nom::character::complete::space0,
+        ),
+        item,
+    )
+}
+
+/// One `item` per line, up to a blank line or the end of the input
+pub fn lines<'a, T>(
+    item: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated(nom::character::complete::newline, item)
+}
+
+/// Blocks separated by blank lines
+pub fn blocks<'a, T>(
+    block: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated(nom::bytes::complete::tag("\n\n"), block)
+}
+
+/// A `key:` followed by its value, on the same line or the lines after it:
+/// `seeds: 79 14` or `seed-to-soil map:\n50 98 2\n52 50 48`
+pub fn section<'a, T>(
+    mut value: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, (&'a str, T)> {
+    move |input| {
+        let (input, key) = nom::error::context(
+            "expected a `key:`",
+            nom::bytes::complete::take_till1(|c| c == ':' || c == '\n'),
+        )(input)?;
+        let (input, _) = literal(":", "expected \":\"")(input)?;
+        let (input, _) = nom::branch::alt((
+            nom::character::complete::space1,
+            nom::character::complete::line_ending,
+        ))(input)?;
+        let (input, value) = value(input)?;
+        Ok((input, (key, value)))
+    }
+}
+
+/// An error for when none of an enum's variants matched
+pub fn expected<'a, T>(input: &'a str, expected: &'static str) -> PResult<'a, T> {
+    let error = VerboseError::from_error_kind(input, ErrorKind::Alt);
+    Err(nom::Err::Error(VerboseError::add_context(
+        input, expected, error,
+    )))
+}
+
+/// Where and why a line didn't parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// The text at the error, up to the end of the line
+    pub found: String,
+}
+
+impl ParseError {
+    /// Locate `error` in `text`, the input that was given to the parser
+    fn new(text: &str, error: VerboseError<&str>) -> Self {
+        let rest = error.errors.first().map_or("", |(rest, _)| *rest);
+        let message = error
+            .errors
+            .iter()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => Some(context.to_string()),
+                _ => None,
+            })
+            .unwrap_or_else(|| match error.errors.first() {
+                Some((_, VerboseErrorKind::Nom(ErrorKind::Eof))) => "unexpected text".to_string(),
+                _ => "invalid input".to_string(),
+            });
+        let offset = text.len() - rest.len();
+        let line_start = text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        Self {
+            line: text[..offset].matches('\n').count() + 1,
+            column: offset - line_start + 1,
+            message,
+            found: rest.lines().next().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        match self.found.chars().count() {
+            0 => write!(f, ", found end of line"),
+            1..=20 => write!(f, ", found {:?}", self.found),
+            _ => write!(
+                f,
+                ", found {:?}...",
+                self.found.chars().take(20).collect::<String>()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse all of `line`, which is line number `line_num` of the input
+pub fn parse_line<T: Parse>(line: &str, line_num: usize) -> Result<T, ParseError> {
+    run(line, T::parser).map_err(|e| ParseError {
+        line: line_num,
+        ..e
+    })
+}
+
+/// Run `parser` on the whole input. Only trailing whitespace can be left over.
+pub fn parse_all<'a, T>(
+    input: &'a str,
+    parser: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> Result<T, ParseError> {
+    run(
+        input,
+        nom::sequence::terminated(parser, nom::character::complete::multispace0),
+    )
+}
+
+/// Run `parser`, which has to consume all of `input`
+fn run<'a, T>(
+    input: &'a str,
+    parser: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> Result<T, ParseError> {
+    match nom::combinator::all_consuming(parser)(input) {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::new(input, e)),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new(
+            input,
+            VerboseError::add_context(
+                "",
+                "incomplete input",
+                VerboseError::from_error_kind("", ErrorKind::Eof),
+            ),
+        )),
+    }
+}
+
+/// Parse every line of `input` as a `T`
+pub fn parse_lines<T: Parse>(input: &str) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse_line(line, idx + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq, Format)]
+    #[format("{id} = ({left}, {right})")]
+    struct Node {
+        id: String,
+        left: String,
+        right: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Format)]
+    enum Step {
+        #[format("{dist} up")]
+        Up { dist: i32 },
+        #[format("down")]
+        Down,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Format)]
+    #[format("steps: {steps:, }")]
+    struct Steps {
+        steps: Vec<Step>,
+    }
+
+    #[test]
+    fn test_derive() {
+        let node: Node = "AAA = (BBB, CCC)".parse().unwrap();
+        assert_eq!(node.left, "BBB");
+        assert_eq!(node.right, "CCC");
+
+        let steps: Steps = "steps: -3 up, down".parse().unwrap();
+        assert_eq!(steps.steps, [Step::Up { dist: -3 }, Step::Down]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parse_lines::<Node>("AAA = (BBB, CCC)\nAAA = [BBB, CCC)\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 4: expected \" = (\", found \" = [BBB, CCC)\""
+        );
+
+        let err = "steps: up".parse::<Steps>().unwrap_err();
+        assert_eq!(err.column, 8);
+        assert_eq!(err.message, "expected \"{dist} up\" or \"down\"");
+
+        let err = "AAA = (BBB, CCC) ".parse::<Node>().unwrap_err();
+        assert_eq!((err.column, err.message.as_str()), (17, "unexpected text"));
+    }
+
+    #[test]
+    fn test_combinators() {
+        let input = "seeds: 79 -14\n\na map:\n1 2\n3 4\n\nb map:\n5 6\n";
+        let bad = input.replace("3 4", "3 x");
+        let mut parser = nom::sequence::separated_pair(
+            section(numbers::<i32>),
+            nom::bytes::complete::tag("\n\n"),
+            blocks(section(lines(numbers::<i32>))),
+        );
+        let (seeds, maps) = parse_all(input, &mut parser).unwrap();
+        assert_eq!(seeds, ("seeds", vec![79, -14]));
+        assert_eq!(maps[0], ("a map", vec![vec![1, 2], vec![3, 4]]));
+        assert_eq!(maps[1], ("b map", vec![vec![5, 6]]));
+
+        let err = parse_all(&bad, &mut parser).unwrap_err();
+        assert_eq!((err.line, err.column), (5, 3));
+        assert_eq!(err.found, "x");
+
+        let list = parse_all("1, 2,3\n", comma_list(u8::parser)).unwrap();
+        assert_eq!(list, [1, 2, 3]);
+        let err = parse_all("1, 2,3x", comma_list(u8::parser)).unwrap_err();
+        assert_eq!((err.column, err.message.as_str()), (7, "unexpected text"));
+    }
+}
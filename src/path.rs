@@ -1,12 +1,12 @@
 use itertools::Itertools;
 
-use crate::{Direction, Grid, Pos};
+use crate::{Coord, Direction, GridMap, Pos};
 
-pub struct Path(pub Vec<PathTile>);
+pub struct Path<C = Pos>(pub Vec<PathTile<C>>);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct PathTile {
-    pub pos: Pos,
+pub struct PathTile<C = Pos> {
+    pub pos: C,
     pub shape: PathShape,
 }
 
@@ -21,26 +21,16 @@ pub enum PathShape {
     SouthWest, // ┑
 }
 
-impl Path {
+impl<C: Coord> Path<C> {
     /// Find a path on a grid
-    pub fn from_grid<T>(grid: &Grid<T>, on_path: impl Fn(&Grid<T>, &Pos) -> bool) -> Option<Self> {
-        let size = grid.size();
+    pub fn from_grid<G>(grid: &G, on_path: impl Fn(&G, &C) -> bool) -> Option<Self>
+    where
+        G: GridMap<Coord = C>,
+    {
         let mut path = vec![];
 
         // Walk in from the left to find an edge
-        let mut start = None;
-        'Y: for y in 0..size.y {
-            for x in 0..size.x {
-                let pos = Pos { x, y };
-                if on_path(grid, &pos) {
-                    start = Some(pos);
-                    break 'Y;
-                }
-            }
-        }
-        let Some(start) = start else {
-            return None;
-        };
+        let start = grid.cells().find(|pos| on_path(grid, pos))?;
         let mut pos = start;
         let mut shape = Self::path_shape(grid, start, &on_path);
         // start walking clockwise
@@ -88,11 +78,10 @@ impl Path {
         Some(Path(path))
     }
 
-    pub fn path_shape<T>(
-        grid: &Grid<T>,
-        pos: Pos,
-        on_path: impl Fn(&Grid<T>, &Pos) -> bool,
-    ) -> PathShape {
+    pub fn path_shape<G>(grid: &G, pos: C, on_path: impl Fn(&G, &C) -> bool) -> PathShape
+    where
+        G: GridMap<Coord = C>,
+    {
         type D = Direction;
 
         let directions = Direction::all()
@@ -143,14 +132,14 @@ impl Path {
         unreachable!("invalid shape {directions:?}");
     }
 
-    pub fn walk(&self) -> impl Iterator<Item = (Pos, Direction)> + '_ {
+    pub fn walk(&self) -> impl Iterator<Item = (C, Direction)> + '_ {
         self.0.iter().enumerate().map(|(idx, tile)| {
             let mut nidx = idx + 1;
             if nidx == self.0.len() {
                 nidx = 0;
             }
 
-            (tile.pos, Direction::heading(tile.pos, self.0[nidx].pos))
+            (tile.pos, tile.pos.heading(&self.0[nidx].pos))
         })
     }
 }
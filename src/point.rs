@@ -0,0 +1,173 @@
+use crate::{Direction, Direction8, Pos};
+use std::num::TryFromIntError;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position that can go negative, for things that move around without a
+/// grid to keep them in bounds. Like `Pos`, y grows to the south.
+///
+/// Points are ordered like text is read: by row, then by column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ZERO: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    /// Distance moving only in the four cardinal directions
+    pub fn manhattan(&self, other: &Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Distance when diagonal steps are allowed too
+    pub fn chebyshev(&self, other: &Point) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+}
+
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Point {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from(value: (i64, i64)) -> Self {
+        Point {
+            x: value.0,
+            y: value.1,
+        }
+    }
+}
+
+/// The unit vector pointing in a direction
+impl From<Direction> for Point {
+    fn from(value: Direction) -> Self {
+        Direction8::from(value).into()
+    }
+}
+
+/// The vector of one step in a direction, so diagonals are (±1, ±1)
+impl From<Direction8> for Point {
+    fn from(value: Direction8) -> Self {
+        let (x, y) = value.offset();
+        Point {
+            x: x as i64,
+            y: y as i64,
+        }
+    }
+}
+
+impl TryFrom<Pos> for Point {
+    type Error = TryFromIntError;
+
+    fn try_from(value: Pos) -> Result<Self, Self::Error> {
+        Ok(Point {
+            x: value.x.try_into()?,
+            y: value.y.try_into()?,
+        })
+    }
+}
+
+/// Fails for points that are left of or above the origin
+impl TryFrom<Point> for Pos {
+    type Error = TryFromIntError;
+
+    fn try_from(value: Point) -> Result<Self, Self::Error> {
+        Ok(Pos {
+            x: value.x.try_into()?,
+            y: value.y.try_into()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let a = Point::new(1, -2);
+        let b = a + Point::from(Direction::West) * 3;
+        assert_eq!(b, Point::new(-2, -2));
+        assert_eq!(b - a, Point::new(-3, 0));
+        assert_eq!(a.manhattan(&Point::new(-1, 2)), 6);
+        assert_eq!(a.chebyshev(&Point::new(-1, 2)), 4);
+        assert_eq!(Point::from(Direction8::SouthWest), Point::new(-1, 1));
+
+        let mut points = vec![Point::new(0, 1), Point::new(5, 0), Point::new(-1, 1)];
+        points.sort();
+        assert_eq!(points, [(5, 0), (-1, 1), (0, 1)].map(Point::from));
+
+        assert!(Pos::try_from(a).is_err());
+        let pos = Pos::try_from(Point::new(3, 4)).unwrap();
+        assert_eq!(Point::try_from(pos), Ok(Point::new(3, 4)));
+    }
+}
@@ -2,12 +2,13 @@ mod day_00; // Template file. Not used, but imported so it will get checked for
 macros::import_solutions!(); // Import the rest of the solution files
 
 use std::io::Read;
+use std::time::Instant;
 
 use aoc_client::SubmissionOutcome;
 use clap::Parser;
-use macros::get_solution;
 
-use crate::{client::Client, RootOpt};
+use crate::history::{self, Entry};
+use crate::{client::Client, OutputFormat, RootOpt};
 
 pub type PuzzleResult = Result<String, anyhow::Error>;
 
@@ -20,6 +21,47 @@ pub trait Puzzle {
     fn part_two(&self, _input: &str) -> PuzzleResult;
 }
 
+/// What a solution is about, declared with `#[puzzle(...)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PuzzleInfo {
+    pub day: u8,
+    pub title: &'static str,
+    pub tags: &'static [&'static str],
+    /// Parts that have been implemented
+    pub parts: &'static [u8],
+}
+
+/// An entry in the solution registry
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub info: PuzzleInfo,
+    pub new: fn(&RootOpt) -> Box<dyn Puzzle>,
+}
+
+impl Solution {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.info.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+impl std::fmt::Debug for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Solution")
+            .field("info", &self.info)
+            .finish()
+    }
+}
+
+/// All solutions, ordered by day
+pub fn solutions() -> &'static [Solution] {
+    SOLUTIONS
+}
+
+/// The solution for `day`, if there is one
+pub fn solution(day: u8) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|s| s.info.day == day)
+}
+
 #[derive(Clone, Debug, Parser, Default)]
 pub struct PuzzleCommand {
     /// Submit the result and update the data files
@@ -29,28 +71,59 @@ pub struct PuzzleCommand {
 
 impl PuzzleCommand {
     pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
-        let client = crate::client::Client::new(opt)?;
+        let day = opt.day()?;
+        log::info!("Running day {} part {}", day, opt.part);
+
         let data = if opt.data {
             let mut data = String::new();
             std::io::stdin().read_to_string(&mut data)?;
-            data
+            crate::input::normalize(&data)
         } else {
-            client.get_input()?
+            Client::new(opt)?.get_input()?
         };
 
-        let day = get_solution!(opt);
+        let entry = solution(day).ok_or_else(|| {
+            anyhow::anyhow!("No solution found for day {day} (src/puzzle/day_{day:02}.rs)")
+        })?;
+        if !entry.info.parts.contains(&opt.part) {
+            anyhow::bail!("Day {day} part {} isn't implemented", opt.part);
+        }
+        let puzzle = (entry.new)(opt);
+        let start = Instant::now();
         let solution = match opt.part {
-            1 => day.part_one(&data)?,
-            2 => day.part_two(&data)?,
-            _ => todo!("Implement part three"),
+            1 => puzzle.part_one(&data)?,
+            2 => puzzle.part_two(&data)?,
+            part => anyhow::bail!("There is no part {part}"),
         };
+        let elapsed = start.elapsed();
+        log::info!("Solved in {elapsed:?}");
+
+        // Runs against ad-hoc data don't belong in the history
+        let year = opt.config.year;
+        if !opt.data {
+            let entry = Entry::run(year, day, opt.part, &solution, elapsed);
+            history::record(&opt.config.history_file, &entry);
+        }
 
-        println!("Solution: {}", solution);
+        match opt.config.output {
+            OutputFormat::Text => println!("Solution: {}", solution),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "year": opt.config.year,
+                    "day": day,
+                    "part": opt.part,
+                    "solution": solution,
+                })
+            ),
+        }
 
         if self.submit {
             let client = Client::new(opt)?;
-            let res = client.client.submit_answer(opt.part as i64, solution)?;
+            let res = client.submit_answer(opt.part, &solution)?;
             println!("{:?}", res);
+            let entry = Entry::submission(year, day, opt.part, &solution, (&res).into());
+            history::record(&opt.config.history_file, &entry);
 
             if matches!(res, SubmissionOutcome::Correct) {
                 println!("Downloading puzzle update");
@@ -1,8 +1,11 @@
 //! This is a template for a puzzle solution.  Copy this file to a new file.
 //! Files in this folder are auto-discovered at build time.
+//! Update the `#[puzzle]` attribute with the day, title and tags of the puzzle.
 
 use super::Puzzle;
+use macros::puzzle;
 
+#[puzzle(day = 0, title = "Template")]
 pub struct Day00;
 
 impl Puzzle for Day00 {
@@ -1,6 +1,8 @@
 use super::Puzzle;
 use itertools::Itertools;
+use macros::puzzle;
 
+#[puzzle(day = 1, title = "Trebuchet?!", tags = ["strings"])]
 pub struct Day01;
 
 impl Puzzle for Day01 {
@@ -1,8 +1,12 @@
 use super::Puzzle;
+use crate::parse::{self, Format, PResult, Parse, ParseError};
+use macros::puzzle;
 
+#[puzzle(day = 2, title = "Cube Conundrum", tags = ["parsing"])]
 pub struct Day02;
 
-#[derive(Debug)]
+#[derive(Debug, Format)]
+#[format("Game {num}: {rounds:; }")]
 struct Game {
     num: usize,
     rounds: Vec<Round>,
@@ -15,13 +19,30 @@ struct Round {
     green: usize,
 }
 
+#[derive(Debug, Format)]
+#[format("{count} {color}")]
+struct Cubes {
+    count: usize,
+    color: Color,
+}
+
+#[derive(Debug, Clone, Copy, Format)]
+enum Color {
+    #[format("red")]
+    Red,
+    #[format("blue")]
+    Blue,
+    #[format("green")]
+    Green,
+}
+
 impl Puzzle for Day02 {
     fn new(_ops: &super::RootOpt) -> Box<dyn Puzzle> {
         Box::new(Self)
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let games = Self::parse_input(input);
+        let games = Self::parse_input(input)?;
         let score = games
             .into_iter()
             .filter(|g| {
@@ -36,7 +57,7 @@ impl Puzzle for Day02 {
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let games = Self::parse_input(input);
+        let games = Self::parse_input(input)?;
         let score = games
             .iter()
             .map(Self::game_mins)
@@ -47,35 +68,8 @@ impl Puzzle for Day02 {
 }
 
 impl Day02 {
-    fn parse_input(input: &str) -> Vec<Game> {
-        let games = input
-            .lines()
-            .map(|line| {
-                let line = line.strip_prefix("Game ").unwrap();
-                let (line, game_num) = nom::character::complete::digit1::<&str, ()>(line).unwrap();
-                let line = line.strip_prefix(": ").unwrap();
-                Game {
-                    num: game_num.parse().unwrap(),
-                    rounds: line.split(';').map(Self::parse_round).collect(),
-                }
-            })
-            .collect();
-
-        games
-    }
-
-    fn parse_round(input: &str) -> Round {
-        let mut round = Round::default();
-        for term in input.split(", ") {
-            let (term, num) = nom::character::complete::digit1::<&str, ()>(term.trim()).unwrap();
-            match term.trim() {
-                "red" => round.red = num.parse().unwrap(),
-                "blue" => round.blue = num.parse().unwrap(),
-                "green" => round.green = num.parse().unwrap(),
-                c => panic!("unexpected color: {}", c),
-            }
-        }
-        round
+    fn parse_input(input: &str) -> Result<Vec<Game>, ParseError> {
+        parse::parse_lines(input)
     }
 
     fn game_mins(game: &Game) -> Round {
@@ -91,6 +85,22 @@ impl Day02 {
     }
 }
 
+/// `3 blue, 4 red`
+impl Parse for Round {
+    fn parser(input: &str) -> PResult<'_, Self> {
+        let (input, cubes) = parse::list::<Cubes>(", ")(input)?;
+        let mut round = Round::default();
+        for Cubes { count, color } in cubes {
+            match color {
+                Color::Red => round.red = count,
+                Color::Blue => round.blue = count,
+                Color::Green => round.green = count,
+            }
+        }
+        Ok((input, round))
+    }
+}
+
 impl Round {
     fn power(&self) -> usize {
         self.red * self.blue * self.green
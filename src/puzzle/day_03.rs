@@ -1,13 +1,12 @@
 use super::Puzzle;
+use crate::{Grid, Pos};
+use macros::puzzle;
 use std::collections::HashMap;
-use std::str::FromStr;
 
+#[puzzle(day = 3, title = "Gear Ratios", tags = ["grid"])]
 pub struct Day03;
 
-struct Schematic(Vec<Vec<Datum>>);
-
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Pos(usize, usize);
+struct Schematic(Grid<Datum>);
 
 #[derive(Debug, Clone, Copy)]
 enum Datum {
@@ -22,7 +21,7 @@ impl Puzzle for Day03 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let data: Schematic = input.parse().unwrap();
+        let data = Schematic(input.parse()?);
         let numbers = data
             .numbers()
             .filter(|(_, pos, len)| !data.adjacent_symbols(*pos, *len).is_empty())
@@ -32,7 +31,7 @@ impl Puzzle for Day03 {
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let data: Schematic = input.parse().unwrap();
+        let data = Schematic(input.parse()?);
         let pairs: HashMap<Pos, Vec<usize>> = data
             .numbers()
             // Find all the numbers that have a star adjacent to them
@@ -65,11 +64,11 @@ impl Day03 {}
 impl Schematic {
     /// Returns an iterator of (number, position, len) for all matches
     fn numbers(&self) -> impl Iterator<Item = (usize, Pos, usize)> + '_ {
-        self.0.iter().enumerate().flat_map(|(y, row)| {
+        self.0.row_slices().enumerate().flat_map(|(y, row)| {
             row.iter()
                 .enumerate()
                 .fold(NumberAccumulator::default(), |mut acc, (x, datum)| {
-                    acc.push(Pos(x, y), *datum);
+                    acc.push(Pos { x, y }, *datum);
                     acc
                 })
                 .complete()
@@ -79,49 +78,11 @@ impl Schematic {
     /// Takes the starting position and length and looks at all the adjacent
     /// cells to see if there are any symbols. Returns a list of what it finds.
     fn adjacent_symbols(&self, pos: Pos, len: usize) -> Vec<(Pos, Datum)> {
-        let Pos(px, py) = pos;
-        let px2 = px + len - 1;
-
-        let left = if px > 0 { px - 1 } else { px };
-        let right = if px2 + 1 < self.0[py].len() {
-            px2 + 1
-        } else {
-            px2
-        };
-
-        let mut symbols = Vec::new();
-
-        // Above
-        if py > 0 {
-            let y = py - 1;
-            symbols.extend(
-                (left..=right)
-                    .map(|x| (Pos(x, y), self.0[y][x]))
-                    .filter(|(_, d)| matches!(d, Datum::Symbol(_))),
-            );
-        }
-
-        // Before
-        if px != left && matches!(self.0[py][left], Datum::Symbol(_)) {
-            symbols.push((Pos(left, py), self.0[py][left]));
-        }
-
-        // After
-        if px != right && matches!(self.0[py][right], Datum::Symbol(_)) {
-            symbols.push((Pos(right, py), self.0[py][right]));
-        }
-
-        // Below
-        if py + 1 < self.0.len() {
-            let y = py + 1;
-            symbols.extend(
-                (left..=right)
-                    .map(|x| (Pos(x, y), self.0[y][x]))
-                    .filter(|(_, d)| matches!(d, Datum::Symbol(_))),
-            );
-        }
-
-        symbols
+        self.0
+            .neighbors_of_span(&pos, len)
+            .map(|p| (p, self.0[p]))
+            .filter(|(_, d)| matches!(d, Datum::Symbol(_)))
+            .collect()
     }
 }
 
@@ -162,23 +123,15 @@ impl NumberAccumulator {
     }
 }
 
-impl FromStr for Schematic {
-    type Err = anyhow::Error;
-
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        Ok(Schematic(
-            input
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|c| match c {
-                            '.' => Datum::None,
-                            _ if c.is_numeric() => Datum::Number(c.to_digit(10).unwrap() as u8),
-                            _ => Datum::Symbol(c),
-                        })
-                        .collect()
-                })
-                .collect(),
-        ))
+impl TryFrom<char> for Datum {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
+            '.' => Datum::None,
+            _ if c.is_numeric() => Datum::Number(c.to_digit(10).unwrap() as u8),
+            _ if c.is_ascii_punctuation() => Datum::Symbol(c),
+            _ => return Err("expected a digit, '.' or a symbol"),
+        })
     }
 }
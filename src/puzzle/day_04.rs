@@ -1,6 +1,8 @@
 use super::Puzzle;
+use macros::puzzle;
 use std::collections::{HashMap, HashSet};
 
+#[puzzle(day = 4, title = "Scratchcards", tags = ["parsing"])]
 pub struct Day04;
 
 struct Card {
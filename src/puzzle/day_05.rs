@@ -3,12 +3,16 @@
 //!       come back, once I'm caught up.
 
 use super::Puzzle;
-use itertools::Itertools;
+use crate::parse::{self, Format, PResult, Parse};
+use macros::puzzle;
+use nom::bytes::complete::tag;
+use nom::sequence::separated_pair;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::collections::BTreeMap;
 use std::ops::Range;
 use std::str::FromStr;
 
+#[puzzle(day = 5, title = "If You Give A Seed A Fertilizer", tags = ["ranges", "brute-force"])]
 pub struct Day05;
 
 #[derive(Debug)]
@@ -31,7 +35,8 @@ struct Table {
     mappings: Vec<Mapping>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Format)]
+#[format("{to} {from} {len}")]
 struct Mapping {
     to: usize,
     from: usize,
@@ -114,49 +119,41 @@ impl FromStr for PuzzleInput {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split("\n\n").collect::<Vec<_>>();
-        let seeds = parts[0]
-            .split(' ')
-            .filter_map(|s| s.parse::<usize>().ok())
-            .collect_vec();
-
-        let seed_ranges = Self::parse_seed_ranges(
-            parts[0]
-                .split(' ')
-                .filter_map(|s| s.parse::<usize>().ok())
-                .collect(),
-        );
+        let ((_, seeds), tables) = parse::parse_all(
+            s,
+            separated_pair(
+                parse::section(parse::numbers),
+                tag("\n\n"),
+                parse::blocks(Table::parser),
+            ),
+        )?;
+        let seed_ranges = Self::parse_seed_ranges(seeds.clone());
+
+        if tables.len() != 7 {
+            anyhow::bail!("expected 7 maps, found {}", tables.len());
+        }
+        let mut tables = tables.into_iter();
+        let mut table = || tables.next().unwrap();
 
         Ok(Self {
             seeds,
             seed_ranges,
-            seed_soil: parts[1].parse()?,
-            soil_fertilizer: parts[2].parse()?,
-            fertilizer_water: parts[3].parse()?,
-            water_light: parts[4].parse()?,
-            light_temp: parts[5].parse()?,
-            temp_humidity: parts[6].parse()?,
-            humidity_location: parts[7].parse()?,
+            seed_soil: table(),
+            soil_fertilizer: table(),
+            fertilizer_water: table(),
+            water_light: table(),
+            light_temp: table(),
+            temp_humidity: table(),
+            humidity_location: table(),
         })
     }
 }
 
-impl FromStr for Table {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let name = lines.next().unwrap().to_string();
-        let mappings = lines
-            .map(|line| {
-                let mut parts = line.split(' ');
-                let to = parts.next().unwrap().parse::<usize>().unwrap();
-                let from = parts.next().unwrap().parse::<usize>().unwrap();
-                let len = parts.next().unwrap().parse::<usize>().unwrap();
-                Mapping { from, to, len }
-            })
-            .collect::<Vec<_>>();
-
-        Ok(Self { name, mappings })
+/// `seed-to-soil map:` followed by a mapping per line
+impl Parse for Table {
+    fn parser(input: &str) -> PResult<'_, Self> {
+        let (input, (name, mappings)) = parse::section(parse::lines(Mapping::parser))(input)?;
+        let name = name.to_string();
+        Ok((input, Self { name, mappings }))
     }
 }
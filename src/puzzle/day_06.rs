@@ -1,5 +1,7 @@
 use super::Puzzle;
+use macros::puzzle;
 
+#[puzzle(day = 6, title = "Wait For It", tags = ["math"])]
 pub struct Day06;
 
 // Input is so small, not bothering with a parser
@@ -2,9 +2,11 @@
 //! Files in this folder are auto-discovered at build time.
 
 use super::Puzzle;
+use macros::puzzle;
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::str::FromStr;
 
+#[puzzle(day = 7, title = "Camel Cards", tags = ["sorting"])]
 pub struct Day07;
 
 #[derive(Debug, Ord, Clone, Copy)]
@@ -50,7 +52,7 @@ impl Puzzle for Day07 {
         let result = hands
             .into_iter()
             .enumerate()
-            .inspect(|(i, h)| println!("{}: {:?} -> {}", i + 1, h, (i + 1) * h.bid))
+            .inspect(|(i, h)| log::debug!("{}: {:?} -> {}", i + 1, h, (i + 1) * h.bid))
             .map(|(i, h)| h.bid * (i + 1))
             .sum::<usize>();
 
@@ -69,7 +71,7 @@ impl Puzzle for Day07 {
         let result = hands
             .into_iter()
             .enumerate()
-            .inspect(|(i, h)| println!("{}: {:?} -> {}", i + 1, h, (i + 1) * h.bid))
+            .inspect(|(i, h)| log::debug!("{}: {:?} -> {}", i + 1, h, (i + 1) * h.bid))
             .map(|(i, h)| h.bid * (i + 1))
             .sum::<usize>();
 
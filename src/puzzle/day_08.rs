@@ -1,17 +1,22 @@
 use super::Puzzle;
+use crate::parse::{self, Format, ParseError};
 use itertools::Itertools;
+use macros::puzzle;
 use std::collections::HashMap;
-use std::str::FromStr;
 
+#[puzzle(day = 8, title = "Haunted Wasteland", tags = ["graph", "cycles"])]
 pub struct Day08;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Format)]
 enum Direction {
+    #[format("L")]
     Left,
+    #[format("R")]
     Right,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Format)]
+#[format("{id} = ({left}, {right})")]
 struct Node {
     id: String,
     left: String,
@@ -31,13 +36,13 @@ impl Puzzle for Day08 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let (directions, nodes) = parse_input(input);
+        let (directions, nodes) = parse_input(input)?;
 
         let mut current_addr = "AAA".to_string();
         let mut steps = 0usize;
         loop {
             for d in &directions {
-                println!("{}: {}", steps, current_addr);
+                log::debug!("{}: {}", steps, current_addr);
                 match d {
                     Direction::Left => current_addr = nodes[&current_addr].left.clone(),
                     Direction::Right => current_addr = nodes[&current_addr].right.clone(),
@@ -51,14 +56,14 @@ impl Puzzle for Day08 {
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let (directions, nodes) = parse_input(input);
+        let (directions, nodes) = parse_input(input)?;
 
         let addrs: Vec<_> = nodes
             .iter()
             .filter(|(id, _)| id.ends_with("A"))
             .map(|(id, _)| id.clone())
             .collect();
-        println!("{:?} starting addrs end with A", addrs.len());
+        log::debug!("{:?} starting addrs end with A", addrs.len());
 
         // Spawn a thread for each starting address
         let mut paths = addrs
@@ -117,52 +122,17 @@ fn compute_period(
     }
 }
 
-fn parse_input(input: &str) -> (Vec<Direction>, HashMap<String, Node>) {
-    let mut lines = input.lines();
+fn parse_input(input: &str) -> Result<(Vec<Direction>, HashMap<String, Node>), ParseError> {
+    let mut lines = input.lines().enumerate();
 
-    let directions: Vec<Direction> = lines
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| c.to_string().parse().unwrap())
-        .collect();
+    let (_, line) = lines.next().unwrap_or_default();
+    let directions: Vec<Direction> = parse::parse_line(line, 1)?;
 
     let nodes: HashMap<String, Node> = lines
-        .filter(|l| !l.is_empty())
-        .map(|l| l.parse().unwrap())
-        .map(|n: Node| (n.id.clone(), n))
-        .collect();
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(idx, l)| parse::parse_line(l, idx + 1))
+        .map_ok(|n: Node| (n.id.clone(), n))
+        .collect::<Result<_, _>>()?;
 
-    (directions, nodes)
-}
-
-impl FromStr for Direction {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "L" => Ok(Self::Left),
-            "R" => Ok(Self::Right),
-            _ => Err(anyhow::anyhow!("invalid direction: {}", s)),
-        }
-    }
-}
-
-impl FromStr for Node {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s
-            .replace('=', "")
-            .replace('(', "")
-            .replace(')', "")
-            .replace(',', "");
-        let mut parts = s.split(' ').filter(|s| !s.is_empty());
-
-        let id = parts.next().unwrap().to_string();
-        let left = parts.next().unwrap().to_string();
-        let right = parts.next().unwrap().to_string();
-
-        Ok(Self { id, left, right })
-    }
+    Ok((directions, nodes))
 }
@@ -1,7 +1,10 @@
 use super::Puzzle;
+use crate::parse::{self, ParseError};
 use itertools::Itertools;
+use macros::puzzle;
 use std::collections::VecDeque;
 
+#[puzzle(day = 9, title = "Mirage Maintenance", tags = ["sequences"])]
 pub struct Day09;
 
 impl Puzzle for Day09 {
@@ -10,7 +13,7 @@ impl Puzzle for Day09 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let input = parse_input(input);
+        let input = parse_input(input)?;
         let result = input
             .into_iter()
             .map(|line| compute_next(line))
@@ -19,7 +22,7 @@ impl Puzzle for Day09 {
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let input = parse_input(input);
+        let input = parse_input(input)?;
         let result = input
             .into_iter()
             .update(|line| line.reverse())
@@ -60,10 +63,6 @@ fn differences(line: &[i64]) -> Vec<i64> {
     result
 }
 
-fn parse_input(input: &str) -> Vec<Vec<i64>> {
-    input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|line| line.split(' ').map(|n| n.parse().unwrap()).collect_vec())
-        .collect_vec()
+fn parse_input(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    parse::parse_all(input, parse::lines(parse::numbers))
 }
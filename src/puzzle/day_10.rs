@@ -1,20 +1,30 @@
 use itertools::Itertools;
 
 use super::Puzzle;
+use crate::{Connectivity, Grid, GridMap, GridTile, Pos, Regions};
+use macros::puzzle;
 use std::collections::HashSet;
-use std::str::FromStr;
 
+#[puzzle(day = 10, title = "Pipe Maze", tags = ["grid", "flood-fill"])]
 pub struct Day10;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, GridTile)]
 enum Piece {
+    #[tile('.')]
     Ground,
+    #[tile('S')]
     Start,
+    #[tile('|')]
     Vertical,
+    #[tile('-')]
     Horizontal,
+    #[tile('L', render = '┖')]
     NorthEast,
+    #[tile('J', render = '┛')]
     NorthWest,
+    #[tile('F', render = '┍')]
     SouthEast,
+    #[tile('7', render = '┑')]
     SouthWest,
 }
 
@@ -35,60 +45,65 @@ impl Puzzle for Day10 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let grid = parse_input(input);
+        let grid: Grid<Piece> = input.parse()?;
         let start = find_start(&grid);
-        println!("Start: {start:?}");
+        log::debug!("Start: {start:?}");
         let path = find_loop(start, &grid);
         let result = (path.len() / 2) + (path.len() & 1);
         Ok(result.to_string())
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let grid = parse_input(input);
+        let grid: Grid<Piece> = input.parse()?;
         let start = find_start(&grid);
         let mut path = find_loop(start, &grid)
             .into_iter()
             .map(|(c, _)| c)
             .collect_vec();
-        let bounds = (grid[0].len(), grid.len());
+        let on_path: HashSet<Pos> = path.iter().map(|&c| c.into()).collect();
+        let regions = grid.regions(Connectivity::Four, |_, p| !on_path.contains(p));
 
-        let mut enclosed = find_enclosed(&path, bounds);
-        if enclosed.iter().any(|c| c.0 == 0 || c.1 == 0) {
+        let mut inside = right_of_path(&path, &regions);
+        if inside.iter().any(|&l| regions.regions[l].touches_border) {
             // we need to walk clockwise around the path for this to work.
             // i don't know how to determine that, so we'll just use the version
             // which doesn't select cells on the border
             path[1..].reverse();
-            enclosed = find_enclosed(&path, bounds);
+            inside = right_of_path(&path, &regions);
         }
 
-        print_grid(&grid, &path, &enclosed.iter().copied().collect_vec());
+        let enclosed = regions
+            .labels
+            .iter()
+            .filter(|(_, l)| inside.contains(l))
+            .map(|(p, _)| Coord(p.x, p.y))
+            .collect_vec();
+        print_grid(&grid, &path, &enclosed);
 
-        let result = enclosed.len();
+        let result = inside
+            .iter()
+            .map(|&l| regions.regions[l].area)
+            .sum::<usize>();
         Ok(result.to_string())
     }
 }
 
-fn find_enclosed(path: &[Coord], bounds: (usize, usize)) -> HashSet<Coord> {
+/// Labels of the regions to the right of the path as it's walked
+fn right_of_path(path: &[Coord], regions: &Regions<Pos>) -> HashSet<usize> {
     let mut enclosed = HashSet::new();
     let mut prev = path[0];
     for (i, &current) in path[1..].iter().enumerate() {
         let heading = Direction::heading(prev, current);
         if let Some(right) = current.step(heading.turn_right()) {
             // println!("{:?} -> {:?} {:?}", prev, current, heading);
-            if !path.contains(&right) {
-                // enclosed.insert(right);
-                flood_fill(right, &mut enclosed, &path, bounds)
-            }
+            enclosed.extend(regions.label(&right.into()));
         }
         // We need to check both sides of a corner before moving on
         if let Some(next) = path.get(i + 2) {
             let heading = Direction::heading(current, *next);
             if let Some(right) = current.step(heading.turn_right()) {
                 // println!("{:?} -> {:?} {:?}", prev, current, heading);
-                if !path.contains(&right) {
-                    // enclosed.insert(right);
-                    flood_fill(right, &mut enclosed, &path, bounds)
-                }
+                enclosed.extend(regions.label(&right.into()));
             }
         }
         prev = current;
@@ -97,7 +112,7 @@ fn find_enclosed(path: &[Coord], bounds: (usize, usize)) -> HashSet<Coord> {
     enclosed
 }
 
-fn find_loop(start: Coord, grid: &[Vec<Piece>]) -> Vec<(Coord, Piece)> {
+fn find_loop(start: Coord, grid: &Grid<Piece>) -> Vec<(Coord, Piece)> {
     // Try talking in each direction until we find a loop
     for dir in [
         Direction::North,
@@ -112,7 +127,7 @@ fn find_loop(start: Coord, grid: &[Vec<Piece>]) -> Vec<(Coord, Piece)> {
     unreachable!("no loop found");
 }
 
-fn walk(start: Coord, mut dir: Direction, grid: &[Vec<Piece>]) -> Option<Vec<(Coord, Piece)>> {
+fn walk(start: Coord, mut dir: Direction, grid: &Grid<Piece>) -> Option<Vec<(Coord, Piece)>> {
     let mut visited = HashSet::new();
 
     let mut piece = start.lookup(grid)?;
@@ -135,57 +150,24 @@ fn walk(start: Coord, mut dir: Direction, grid: &[Vec<Piece>]) -> Option<Vec<(Co
     }
 }
 
-fn find_start(grid: &[Vec<Piece>]) -> Coord {
-    grid.iter()
-        .enumerate()
-        .find_map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .find(|(_, p)| **p == Piece::Start)
-                .map(|(x, _)| Coord(x, y))
-        })
-        .unwrap()
+fn find_start(grid: &Grid<Piece>) -> Coord {
+    let pos = grid.find(&Piece::Start).unwrap();
+    Coord(pos.x, pos.y)
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Piece>> {
-    input
-        .lines()
-        .filter(|s| !s.is_empty())
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect_vec()
-        })
-        .collect_vec()
-}
-
-fn flood_fill(coord: Coord, data: &mut HashSet<Coord>, path: &[Coord], bounds: (usize, usize)) {
-    type D = Direction;
-    let mut next = vec![coord];
-    while let Some(v) = next.pop() {
-        data.insert(v);
-        for d in [D::North, D::East, D::West, D::South] {
-            let Some(c) = v.step(d) else {
-                continue;
-            };
-            if c.0 >= bounds.0 || c.1 >= bounds.1 {
-                continue;
-            }
-            if data.contains(&c) {
-                continue;
-            }
-            if path.contains(&c) {
-                continue;
-            }
-            next.push(c);
+impl From<Coord> for Pos {
+    fn from(value: Coord) -> Self {
+        Pos {
+            x: value.0,
+            y: value.1,
         }
     }
 }
 
 impl Coord {
     /// Get piece located at coord, if valid
-    fn lookup(&self, grid: &[Vec<Piece>]) -> Option<Piece> {
-        grid.get(self.1).and_then(|row| row.get(self.0)).cloned()
+    fn lookup(&self, grid: &Grid<Piece>) -> Option<Piece> {
+        grid.value(&(*self).into()).copied()
     }
 
     /// Walk in a direction
@@ -264,14 +246,16 @@ impl Direction {
 }
 
 /// Print a colorized version of the grid, to see the path
-fn print_grid(grid: &[Vec<Piece>], path: &[Coord], enclosed: &[Coord]) {
+fn print_grid(grid: &Grid<Piece>, path: &[Coord], enclosed: &[Coord]) {
     use colored::Colorize;
 
-    for (y, line) in grid.iter().enumerate() {
-        println!(
+    if !log::log_enabled!(log::Level::Debug) {
+        return;
+    }
+    for (y, line) in grid.rows().enumerate() {
+        log::debug!(
             "{}",
-            line.iter()
-                .enumerate()
+            line.enumerate()
                 .map(|(x, p)| {
                     let coord = Coord(x, y);
                     let mut p = p.to_string().normal();
@@ -294,36 +278,3 @@ fn print_grid(grid: &[Vec<Piece>], path: &[Coord], enclosed: &[Coord]) {
         );
     }
 }
-
-impl std::fmt::Display for Piece {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
-            Self::Ground => ".",
-            Self::Start => "S",
-            Self::Vertical => "|",
-            Self::Horizontal => "-",
-            Self::NorthEast => "┖",
-            Self::NorthWest => "┛",
-            Self::SouthEast => "┍",
-            Self::SouthWest => "┑",
-        })
-    }
-}
-
-impl FromStr for Piece {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "." => Self::Ground,
-            "S" => Self::Start,
-            "|" => Self::Vertical,
-            "-" => Self::Horizontal,
-            "L" => Self::NorthEast,
-            "J" => Self::NorthWest,
-            "F" => Self::SouthEast,
-            "7" => Self::SouthWest,
-            _ => anyhow::bail!("Invalid tile: {s}"),
-        })
-    }
-}
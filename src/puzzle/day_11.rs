@@ -1,6 +1,9 @@
 use super::Puzzle;
+use crate::{BitGrid, Grid, GridError};
 use itertools::Itertools;
+use macros::puzzle;
 
+#[puzzle(day = 11, title = "Cosmic Expansion", tags = ["grid", "distance"])]
 pub struct Day11;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -12,8 +15,8 @@ impl Puzzle for Day11 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let galaxies = parse_input(input, 2);
-        println!("{:#?}", galaxies);
+        let galaxies = parse_input(input, 2)?;
+        log::debug!("{:#?}", galaxies);
         let result = galaxies
             .iter()
             .combinations(2)
@@ -24,8 +27,8 @@ impl Puzzle for Day11 {
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let galaxies = parse_input(input, 1000000);
-        println!("{:#?}", galaxies);
+        let galaxies = parse_input(input, 1000000)?;
+        log::debug!("{:#?}", galaxies);
         let result = galaxies
             .iter()
             .combinations(2)
@@ -45,45 +48,29 @@ impl Coord {
 }
 
 /// Just returns the list of galaxies
-fn parse_input(input: &str, expansion: usize) -> Vec<Coord> {
-    // 2D array of bools. `true` means there is a galaxy
-    let grid = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|line| line.chars().map(|c| c == '#').collect_vec())
-        .collect_vec();
-
-    let empty_rows = grid
-        .iter()
-        .enumerate()
-        .filter(|(_, row)| row.iter().all(|&v| v == false))
-        .map(|(y, _)| y)
-        .collect_vec();
+fn parse_input(input: &str, expansion: usize) -> Result<Vec<Coord>, GridError> {
+    let grid = Grid::parse_rows(input.lines().filter(|l| !l.is_empty()), |c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err("expected '#' or '.'"),
+    })?;
+    // A set bit means there is a galaxy
+    let galaxies = BitGrid::from_grid(&grid, |&g| g);
 
-    let width = grid[0].len();
-    let empty_cols = (0..width)
-        .into_iter()
-        .filter(|&i| grid.iter().all(|row| row[i] == false))
+    let size = galaxies.size();
+    let empty_rows = (0..size.y)
+        .filter(|&y| galaxies.row_count(y) == 0)
         .collect_vec();
-
-    let galaxies = grid
-        .iter()
-        .enumerate()
-        .map(|(y, row)| {
-            let empty_row_count = empty_rows.iter().filter(|z| **z < y).count();
-            let y = y + (empty_row_count * expansion) - empty_row_count;
-            row.iter()
-                .enumerate()
-                .filter(|(_, &v)| v)
-                .map(|(x, _)| {
-                    let empty_col_count = empty_cols.iter().filter(|z| **z < x).count();
-                    let x = x + (empty_col_count * expansion) - empty_col_count;
-                    Coord(x, y)
-                })
-                .collect_vec()
-        })
-        .flatten()
+    let empty_cols = (0..size.x)
+        .filter(|&x| galaxies.col_count(x) == 0)
         .collect_vec();
 
-    galaxies
+    let expand = |i: usize, empty: &[usize]| {
+        let empty_count = empty.iter().filter(|z| **z < i).count();
+        i + (empty_count * expansion) - empty_count
+    };
+    Ok(galaxies
+        .ones()
+        .map(|pos| Coord(expand(pos.x, &empty_cols), expand(pos.y, &empty_rows)))
+        .collect())
 }
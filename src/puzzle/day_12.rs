@@ -2,8 +2,11 @@ use itertools::Itertools;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use super::Puzzle;
-use std::{collections::HashMap, str::FromStr};
+use crate::memo::memoize;
+use macros::puzzle;
+use std::str::FromStr;
 
+#[puzzle(day = 12, title = "Hot Springs", tags = ["memoization", "recursion"])]
 pub struct Day12;
 
 #[derive(Debug, Clone)]
@@ -66,19 +69,13 @@ impl Sequence {
 
     fn arrangements(&self) -> usize {
         // println!("start {:?} {:?}", &self.states, &self.runs);
-        let mut memo = Default::default();
-        Self::possible_arrangements(&mut memo, &self.states, &self.runs)
+        Self::possible_arrangements(&self.states, &self.runs)
     }
 
-    fn possible_arrangements<'a, 'b>(
-        memo: &'b mut HashMap<(&'a [State], &'a [usize]), usize>,
-        states: &'a [State],
-        runs: &'a [usize],
-    ) -> usize {
-        if let Some(v) = memo.get(&(states, runs)) {
-            return *v;
-        }
-
+    // Every call gets a suffix of the same states and runs, so lengths are a
+    // good enough key
+    #[memoize(key = (states.len(), runs.len()), key_type = (usize, usize))]
+    fn possible_arrangements(states: &[State], runs: &[usize]) -> usize {
         // if there are no more runs, there can't be any more damaged nodes
         if runs.is_empty() {
             if states.contains(&State::Damaged) {
@@ -101,7 +98,7 @@ impl Sequence {
         let mut sum = 0usize;
         // Try the next state as . by just stepping forward
         if [State::Unknown, State::Operational].contains(&states[0]) {
-            sum += Self::possible_arrangements(memo, &states[1..], runs);
+            sum += Self::possible_arrangements(&states[1..], runs);
         }
         // Try the next state as # by trying to consume the expected run length
         if [State::Unknown, State::Damaged].contains(&states[0]) {
@@ -111,15 +108,14 @@ impl Sequence {
             if states.len() >= next && !states[..next].contains(&State::Operational) {
                 if states.len() == next {
                     // run is the same length as `next`
-                    sum += Self::possible_arrangements(memo, &states[next..], &runs[1..]);
+                    sum += Self::possible_arrangements(&states[next..], &runs[1..]);
                 } else if states[next] != State::Damaged {
                     // run is no longer than `next`
-                    sum += Self::possible_arrangements(memo, &states[(next + 1)..], &runs[1..]);
+                    sum += Self::possible_arrangements(&states[(next + 1)..], &runs[1..]);
                 }
             }
         }
 
-        memo.insert((states, runs), sum);
         // println!("{states:?} {runs:?} {sum}");
         sum
     }
@@ -1,22 +1,22 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    str::FromStr,
-};
-
-use itertools::Itertools;
+use std::collections::VecDeque;
 
 use super::Puzzle;
-use crate::Pos;
+use crate::{cycle, GridError, GridTile, Pos};
+use macros::puzzle;
 
+#[puzzle(day = 14, title = "Parabolic Reflector Dish", tags = ["grid", "cycles"])]
 pub struct Day14;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Grid(Vec<Vec<Position>>);
+struct Grid(crate::Grid<Position>);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, GridTile)]
 enum Position {
+    #[tile('.')]
     Empty,
+    #[tile('#')]
     Cube,
+    #[tile('O')]
     Sphere,
 }
 
@@ -34,60 +34,38 @@ impl Puzzle for Day14 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let mut grid = parse_input(input);
+        let mut grid = parse_input(input)?;
         grid.tilt(Direction::North);
         Ok(grid.total_load().to_string())
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let input = parse_input(input);
-        let mut grid = &input;
-        let mut memo: HashMap<Grid, (Grid, usize)> = Default::default();
-        let mut i = 0usize;
-        let mut found_cycle = false;
-        let iterations = 1000000000usize;
-        while i < iterations {
-            if !found_cycle {
-                if let Some((next, seen)) = memo.get(grid) {
-                    // once we see a cycle, we can skip all iterations of it
-                    // println!("Found cycle at: {i} (seen: {seen})");
-                    let cycle_len = i - *seen;
-                    let remaining = (iterations - i) % cycle_len;
-                    i = iterations - remaining + 1;
-                    // println!("Jumping to {i}");
-
-                    found_cycle = true;
-                    grid = next;
-                    continue;
-                }
-            }
-
-            let prev = grid.clone();
-            let mut next = grid.clone();
-            next.tilt(Direction::North);
-            next.tilt(Direction::West);
-            next.tilt(Direction::South);
-            next.tilt(Direction::East);
-            memo.insert(prev.clone(), (next, i));
-
-            grid = &memo.get(&prev).unwrap().0;
-            i += 1;
-        }
+        let grid = parse_input(input)?;
+        let grid = cycle::nth_state(grid, Grid::spin, 1_000_000_000);
         Ok(grid.total_load().to_string())
     }
 }
 
 impl Grid {
+    /// Tilt north, west, south, then east
+    fn spin(&self) -> Self {
+        let mut next = self.clone();
+        for direction in [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ] {
+            next.tilt(direction);
+        }
+        next
+    }
+
     fn total_load(&self) -> usize {
         let size = self.size();
         let mut load = 0usize;
-        for (y, row) in self.0.iter().enumerate() {
-            let mut count = 0usize;
-            for (_x, cell) in row.iter().enumerate() {
-                if *cell == Position::Sphere {
-                    count += 1;
-                }
-            }
+        for (y, row) in self.0.row_slices().enumerate() {
+            let count = row.iter().filter(|&&cell| cell == Position::Sphere).count();
             load += count * (size.y - y);
         }
 
@@ -95,63 +73,35 @@ impl Grid {
     }
 
     fn size(&self) -> Pos {
-        Pos::size_of(&self.0)
-    }
-
-    #[inline]
-    fn swap(&mut self, pos: Pos, open: &mut VecDeque<Pos>) {
-        let x = pos.x;
-        let y = pos.y;
-        let data = self.0[y][x];
-        match data {
-            Position::Empty => open.push_back(pos),
-            Position::Cube => open.clear(),
-            Position::Sphere => {
-                if let Some(op) = open.pop_front() {
-                    self.0[y][x] = Position::Empty;
-                    self.0[op.y][op.x] = Position::Sphere;
-                    open.push_back(pos);
-                }
-            }
-        }
+        self.0.size()
     }
 
-    /// Shift all movable objects one unit in the specified direction
-    /// Returns the number of objects that moved
+    /// Shift all movable objects as far as they go in the specified direction
     fn tilt(&mut self, direction: Direction) {
-        let size = self.size();
-        let mut open_pos: VecDeque<Pos> = VecDeque::with_capacity(32);
-
-        match direction {
-            Direction::North => {
-                for x in 0..size.x {
-                    open_pos.clear();
-                    for y in 0..size.y {
-                        self.swap((x, y).into(), &mut open_pos);
-                    }
-                }
-            }
-            Direction::South => {
-                for x in 0..size.x {
-                    open_pos.clear();
-                    for y in (0..size.y).rev() {
-                        self.swap((x, y).into(), &mut open_pos);
-                    }
-                }
-            }
-            Direction::West => {
-                for y in 0..size.y {
-                    open_pos.clear();
-                    for x in 0..size.x {
-                        self.swap((x, y).into(), &mut open_pos);
-                    }
-                }
-            }
-            Direction::East => {
-                for y in 0..size.y {
-                    open_pos.clear();
-                    for x in (0..size.x).rev() {
-                        self.swap((x, y).into(), &mut open_pos);
+        // Turn the grid so that `direction` is north in the view
+        let view = self.0.view_mut();
+        let mut view = match direction {
+            Direction::North => view,
+            Direction::South => view.flipped_vertical(),
+            Direction::West => view.transposed(),
+            Direction::East => view.rotated_ccw(),
+        };
+
+        let size = view.size();
+        let mut open: VecDeque<Pos> = VecDeque::with_capacity(32);
+        for x in 0..size.x {
+            open.clear();
+            for y in 0..size.y {
+                let pos = Pos { x, y };
+                match view[pos] {
+                    Position::Empty => open.push_back(pos),
+                    Position::Cube => open.clear(),
+                    Position::Sphere => {
+                        if let Some(op) = open.pop_front() {
+                            view[pos] = Position::Empty;
+                            view[op] = Position::Sphere;
+                            open.push_back(pos);
+                        }
                     }
                 }
             }
@@ -159,28 +109,6 @@ impl Grid {
     }
 }
 
-fn parse_input(input: &str) -> Grid {
-    let data = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect_vec()
-        })
-        .collect_vec();
-
-    Grid(data)
-}
-
-impl FromStr for Position {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "." => Ok(Self::Empty),
-            "#" => Ok(Self::Cube),
-            "O" => Ok(Self::Sphere),
-            other => Err(anyhow::anyhow!("Unexpected symbol: {other}")),
-        }
-    }
+fn parse_input(input: &str) -> Result<Grid, GridError> {
+    Ok(Grid(input.parse()?))
 }
@@ -1,12 +1,16 @@
-use itertools::Itertools;
-
 use super::Puzzle;
+use crate::parse::{self, Format, Parse, ParseError};
+use macros::puzzle;
 
+#[puzzle(day = 15, title = "Lens Library", tags = ["hashing"])]
 pub struct Day15;
 
+#[derive(Format)]
 enum Instruction {
-    Insert(u8),
-    Remove,
+    #[format("{label}={focal_length}")]
+    Insert { label: String, focal_length: u8 },
+    #[format("{label}-")]
+    Remove { label: String },
 }
 
 impl Puzzle for Day15 {
@@ -21,20 +25,24 @@ impl Puzzle for Day15 {
             .unwrap()
             .split(',')
             .map(|s| hash(s))
-            .inspect(|h| println!("{h}"))
+            .inspect(|h| log::debug!("{h}"))
             .sum::<usize>();
         Ok(result.to_string())
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let steps = parse_instructions(input);
+        let steps = parse_instructions(input)?;
         let mut boxen: Vec<Vec<(String, usize)>> = Vec::from_iter((0..255).map(|_| vec![]));
 
         // Process the instructions
-        for (id, box_idx, instruction) in steps {
+        for instruction in steps {
             match instruction {
-                Instruction::Remove => boxen[box_idx].retain(|v| v.0 != id),
-                Instruction::Insert(val) => {
+                Instruction::Remove { label: id } => boxen[hash(&id)].retain(|v| v.0 != id),
+                Instruction::Insert {
+                    label: id,
+                    focal_length: val,
+                } => {
+                    let box_idx = hash(&id);
                     let found = boxen[box_idx]
                         .iter()
                         .enumerate()
@@ -77,24 +85,6 @@ fn hash(input: &str) -> usize {
     return hash;
 }
 
-fn parse_instructions(input: &str) -> Vec<(String, usize, Instruction)> {
-    input
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|i| {
-            if i.contains('-') {
-                let id = i.split('-').next().unwrap().to_string();
-                (id.clone(), hash(&id), Instruction::Remove)
-            } else {
-                let parts = i.split('=').collect_vec();
-                (
-                    parts[0].to_string(),
-                    hash(parts[0]),
-                    Instruction::Insert(parts[1].parse().unwrap()),
-                )
-            }
-        })
-        .collect_vec()
+fn parse_instructions(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse::parse_all(input, parse::comma_list(Instruction::parser))
 }
@@ -1,16 +1,24 @@
 use super::Puzzle;
-use crate::{Direction, Grid, Pos};
+use crate::{BitGrid, Direction, Grid, GridTile, Pos};
+use macros::puzzle;
 use rayon::prelude::*;
-use std::{collections::HashSet, str::FromStr};
+use std::collections::HashSet;
 
+#[puzzle(day = 16, title = "The Floor Will Be Lava", tags = ["grid", "simulation"])]
 pub struct Day16;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, GridTile)]
+#[tile(debug)]
 enum Tile {
+    #[tile('.')]
     Empty,
+    #[tile('/')]
     NWMirror,
+    #[tile('\\')]
     NEMirror,
+    #[tile('-')]
     HorizSplit,
+    #[tile('|')]
     VertSplit,
 }
 
@@ -26,14 +34,14 @@ impl Puzzle for Day16 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let input: Grid<Tile> = input.parse().unwrap();
+        let input: Grid<Tile> = input.parse()?;
         input.debug_print(|_pos, tile| format!("{:?}", tile));
         let result = summarize_ray(Ray::start(), &input);
         Ok(result.to_string())
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let input: Grid<Tile> = input.parse().unwrap();
+        let input: Grid<Tile> = input.parse()?;
         let size = input.size();
         let top = (0..size.x).map(|x| Pos { x, y: 0 }).map(|pos| Ray {
             pos,
@@ -74,44 +82,32 @@ fn summarize_ray(ray: Ray, grid: &Grid<Tile>) -> usize {
     let size = grid.size();
     let mut rays = vec![ray];
     let mut seen_rays = HashSet::new();
-    let mut visited: Grid<bool> = Grid::new(size.x, size.y, false);
+    let mut visited = BitGrid::new(size.x, size.y);
 
-    // Walk each ray, one at a time
-    while let Some(mut ray) = rays.pop() {
+    // Each ray starts on a tile that sends it somewhere, and goes straight
+    // until it meets the next one
+    while let Some(ray) = rays.pop() {
+        if !seen_rays.insert(ray) {
+            continue;
+        }
         log::debug!("Following: {ray:?}");
-        let start = ray.pos;
-        let mut loop_detection = HashSet::new();
-        loop_detection.insert(ray);
-
         visited.set(&ray.pos, true);
-        loop {
-            let (done, new_ray) = ray.step(grid);
-            if let Some(r) = new_ray {
-                if !seen_rays.contains(&r) {
-                    log::debug!("New ray {r:?}");
-                    rays.push(r.clone());
-                    seen_rays.insert(r);
-                }
-            }
-            if done {
-                break;
-            } else {
-                visited.set(&ray.pos, true);
-                if loop_detection.contains(&ray) {
+
+        for facing in grid[ray.pos].redirect(ray.facing).into_iter().flatten() {
+            for (pos, tile) in grid.ray(ray.pos, facing) {
+                visited.set(&pos, true);
+                if tile.redirects(facing) {
+                    rays.push(Ray { pos, facing });
                     break;
                 }
-                loop_detection.insert(ray);
             }
         }
-        print_grid(&visited, Some(start));
+        print_grid(&visited, Some(ray.pos));
         log::debug!("");
     }
 
     print_grid(&visited, None);
-    visited
-        .rows()
-        .map(|row| row.filter(|&&v| v).count())
-        .sum::<usize>()
+    visited.count_ones()
 }
 
 impl Ray {
@@ -121,108 +117,43 @@ impl Ray {
             facing: Direction::East,
         }
     }
-
-    /// Step forward. Returns true on done
-    /// If there's a split, return one of them and follows the other
-    fn step(&mut self, grid: &Grid<Tile>) -> (bool, Option<Ray>) {
-        let value = *grid.value(&self.pos).unwrap();
-        match value {
-            Tile::Empty => self.step_unchecked(grid, None),
-            Tile::NWMirror => {
-                // "/"
-                log::debug!("[/] {:?} {:?}", self.pos, self.facing);
-                match self.facing {
-                    Direction::North => self.facing = Direction::East,
-                    Direction::East => self.facing = Direction::North,
-                    Direction::South => self.facing = Direction::West,
-                    Direction::West => self.facing = Direction::South,
-                };
-                log::debug!("{:?}", self.facing);
-                self.step_unchecked(grid, None)
-            }
-            Tile::NEMirror => {
-                // "\"
-                log::debug!("[\\] {:?} {:?}", self.pos, self.facing);
-                match self.facing {
-                    Direction::North => self.facing = Direction::West,
-                    Direction::East => self.facing = Direction::South,
-                    Direction::South => self.facing = Direction::East,
-                    Direction::West => self.facing = Direction::North,
-                };
-                log::debug!("{:?}", self.facing);
-                self.step_unchecked(grid, None)
-            }
-            Tile::HorizSplit => match self.facing {
-                Direction::East | Direction::West => self.step_unchecked(grid, None),
-                Direction::North | Direction::South => {
-                    log::debug!("[-] {:?} {:?}", self.pos, self.facing);
-                    let mut other = self.clone();
-                    other.facing = Direction::East;
-                    self.facing = Direction::West;
-                    log::debug!("{:?}", self.facing);
-                    self.step_unchecked(grid, Some(other))
-                }
-            },
-            Tile::VertSplit => match self.facing {
-                Direction::North | Direction::South => self.step_unchecked(grid, None),
-                Direction::East | Direction::West => {
-                    log::debug!("[|] {:?} {:?}", self.pos, self.facing);
-                    let mut other = self.clone();
-                    other.facing = Direction::South;
-                    self.facing = Direction::North;
-                    log::debug!("{:?}", self.facing);
-                    self.step_unchecked(grid, Some(other))
-                }
-            },
-        }
-    }
-
-    /// Just walk in a direction without looking at the tile
-    fn step_unchecked(&mut self, grid: &Grid<Tile>, other: Option<Ray>) -> (bool, Option<Ray>) {
-        match grid.step(&self.pos, self.facing) {
-            Some(p) => {
-                self.pos = p;
-                (false, other)
-            }
-            None => (true, other),
-        }
-    }
 }
 
-impl FromStr for Tile {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // log::debug!("{s}");
-        match s {
-            "." => Ok(Self::Empty),
-            "/" => Ok(Self::NWMirror),
-            "\\" => Ok(Self::NEMirror),
-            "-" => Ok(Self::HorizSplit),
-            "|" => Ok(Self::VertSplit),
-            other => Err(anyhow::anyhow!("Unexpected symbol: {other}")),
-        }
+impl Tile {
+    /// Whether a ray heading in `facing` does anything but pass through
+    fn redirects(&self, facing: Direction) -> bool {
+        self.redirect(facing) != [Some(facing), None]
     }
-}
 
-impl std::fmt::Debug for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
-            Self::Empty => ".",
-            Self::NWMirror => "/",
-            Self::NEMirror => "\\",
-            Self::HorizSplit => "-",
-            Self::VertSplit => "|",
-        };
-        f.write_str(s)
+    /// Where a ray heading in `facing` goes from here. Splitters send it two
+    /// ways, everything else fills only the first slot.
+    fn redirect(&self, facing: Direction) -> [Option<Direction>; 2] {
+        type D = Direction;
+        match (self, facing) {
+            (Tile::Empty, _) => [Some(facing), None],
+            // "/"
+            (Tile::NWMirror, D::North) => [Some(D::East), None],
+            (Tile::NWMirror, D::East) => [Some(D::North), None],
+            (Tile::NWMirror, D::South) => [Some(D::West), None],
+            (Tile::NWMirror, D::West) => [Some(D::South), None],
+            // "\"
+            (Tile::NEMirror, D::North) => [Some(D::West), None],
+            (Tile::NEMirror, D::East) => [Some(D::South), None],
+            (Tile::NEMirror, D::South) => [Some(D::East), None],
+            (Tile::NEMirror, D::West) => [Some(D::North), None],
+            (Tile::HorizSplit, D::East | D::West) => [Some(facing), None],
+            (Tile::HorizSplit, D::North | D::South) => [Some(D::East), Some(D::West)],
+            (Tile::VertSplit, D::North | D::South) => [Some(facing), None],
+            (Tile::VertSplit, D::East | D::West) => [Some(D::North), Some(D::South)],
+        }
     }
 }
 
-fn print_grid(grid: &Grid<bool>, highlight: Option<Pos>) {
-    grid.debug_print(|pos, c| {
+fn print_grid(grid: &BitGrid, highlight: Option<Pos>) {
+    grid.debug_print(|pos, visited| {
         if Some(pos) == highlight {
             "*".to_string()
-        } else if *c {
+        } else if visited {
             "#".to_string()
         } else {
             ".".to_string()
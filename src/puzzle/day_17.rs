@@ -1,21 +1,21 @@
-use std::hash::Hash;
-
 use super::Puzzle;
-use crate::{Direction, Grid, Pos};
+use crate::{Direction, Grid, Pos, SearchState};
+use macros::puzzle;
+use std::collections::HashSet;
 
+#[puzzle(day = 17, title = "Clumsy Crucible", tags = ["grid", "pathfinding"])]
 pub struct Day17;
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
-struct Path<'a> {
+/// Where a crucible is, and how long it's been going in a straight line
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct State {
     pos: Pos,
     heading: Direction,
     run_len: u8,
-    grid: &'a Grid<u8>,
-    goal: Pos,
     crucible: Crucible,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 enum Crucible {
     Normal,
     Ultra,
@@ -35,108 +35,78 @@ impl Puzzle for Day17 {
     }
 }
 
-fn solve_puzzle(input: &str, crucuble: Crucible) -> super::PuzzleResult {
-    let input: Grid<u8> = input.parse().unwrap();
+fn solve_puzzle(input: &str, crucible: Crucible) -> super::PuzzleResult {
+    let input = Grid::parse_with(input, |c| {
+        c.to_digit(10).map(|d| d as u8).ok_or("expected a digit")
+    })?;
     let size = input.size();
-    let start = Pos::ZERO;
     let end = Pos::from((size.x - 1, size.y - 1));
 
-    let path = Path {
-        pos: start,
+    let start = State {
+        pos: Pos::ZERO,
         heading: Direction::East,
         run_len: 0,
-        grid: &input,
-        goal: end,
-        crucible: crucuble,
+        crucible,
     };
-    let Some((_, result)) = pathfinding::directed::astar::astar(
-        &path,
-        Path::astar_succ,
-        Path::guess_cost_to_end,
-        Path::is_complete,
+    let Some(route) = input.astar(
+        start,
+        end,
+        |_, &loss| Some(loss as usize),
+        |s| s.is_complete(end),
     ) else {
         anyhow::bail!("no path found");
     };
 
-    Ok(result.to_string())
-}
-
-impl<'a> Path<'a> {
-    fn curr_cost(&self) -> usize {
-        *self.grid.value(&self.pos).unwrap() as usize
-    }
+    let path = route.positions().collect::<HashSet<_>>();
+    input.debug_print(|pos, loss| {
+        if path.contains(&pos) {
+            "#".to_string()
+        } else {
+            loss.to_string()
+        }
+    });
 
-    fn guess_cost_to_end(&self) -> usize {
-        let curr_pos = self.pos;
-        let size = self.grid.size();
-        let x_dist = size.x - curr_pos.x;
-        let y_dist = size.y - curr_pos.y;
-        f64::sqrt(((x_dist * x_dist) + (y_dist * y_dist)) as f64) as usize
-    }
+    Ok(route.cost.to_string())
+}
 
-    fn is_complete(&self) -> bool {
+impl State {
+    fn is_complete(&self, goal: Pos) -> bool {
         // Can only stop after at least 4 blocks
-        if self.crucible == Crucible::Ultra && (self.run_len < 4 || self.run_len > 10) {
+        if self.crucible == Crucible::Ultra && self.run_len < 4 {
             return false;
         }
 
-        self.pos == self.goal
+        self.pos == goal
     }
+}
 
-    fn step(&self, d: Direction) -> Option<Self> {
-        let next_pos = self.grid.step(&self.pos, d)?;
-        let new_heading = Direction::heading(self.pos, next_pos);
-        let mut next = self.clone();
-        next.pos = next_pos;
-        next.heading = new_heading;
-        if next.heading == self.heading {
-            next.run_len += 1;
-        } else {
-            next.run_len = 1;
-        }
-
-        // validate
-        match self.crucible {
-            Crucible::Normal => {
-                // no more than 3 blocks without a turn
-                if next.run_len > 3 {
-                    return None;
-                }
-            }
-            Crucible::Ultra => {
-                // can only turn after between 4-10 blocks
-                if self.heading != next.heading && (self.run_len < 4 || self.run_len > 10) {
-                    return None;
-                }
-            }
-        }
-
-        Some(next)
+impl SearchState for State {
+    fn pos(&self) -> Pos {
+        self.pos
     }
 
-    fn astar_succ(&self) -> Vec<(Self, usize)> {
-        let mut succ = Vec::with_capacity(4);
-        let heading = self.heading;
-        for d in [heading, heading.turn_right(), heading.turn_left()] {
-            let Some(next) = self.step(d) else {
-                continue;
-            };
-            let cost = next.curr_cost();
-            succ.push((next, cost));
+    fn advance(&self, pos: Pos, heading: Direction) -> Option<Self> {
+        // It's only possible to turn around at the start, which leaves the grid
+        if heading == -self.heading {
+            return None;
         }
-        // for (p, c) in &succ {
-        //     let dp: Vec<(usize, usize)> = p.path.iter().copied().map(Into::into).collect();
-        //     println!("{:?} -> {}", dp, c);
-        // }
+        let turned = heading != self.heading;
+        let run_len = if turned { 1 } else { self.run_len + 1 };
 
-        succ
-    }
-}
+        match self.crucible {
+            // no more than 3 blocks without a turn
+            Crucible::Normal if run_len > 3 => return None,
+            // can only turn after between 4-10 blocks, unless just starting
+            Crucible::Ultra if run_len > 10 => return None,
+            Crucible::Ultra if turned && (1..4).contains(&self.run_len) => return None,
+            _ => {}
+        }
 
-impl<'a> Hash for Path<'a> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.pos.hash(state);
-        self.run_len.hash(state);
-        self.heading.hash(state);
+        Some(State {
+            pos,
+            heading,
+            run_len,
+            crucible: self.crucible,
+        })
     }
 }
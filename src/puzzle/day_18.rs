@@ -1,11 +1,10 @@
-use std::{fmt::Debug, str::FromStr};
-
-use itertools::Itertools;
-
-use crate::{Direction, Grid, Path, Pos};
+use crate::parse::{self, Format, PResult, Parse, ParseError};
+use crate::{Connectivity, Direction, GridMap, Point, SparseGrid};
 
 use super::Puzzle;
+use macros::puzzle;
 
+#[puzzle(day = 18, title = "Lavaduct Lagoon", tags = ["grid", "geometry"])]
 pub struct Day18;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,11 +13,39 @@ struct Instruction {
     dist: usize,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Default)]
-enum Tile {
-    #[default]
-    Ground,
-    Hole,
+/// A line of the dig plan, like `R 6 (#70c710)`. The colour turns out to be
+/// the real instruction for part two.
+#[derive(Debug, Format)]
+#[format("{dir} {dist} (#{color})")]
+struct PlanStep {
+    dir: Heading,
+    dist: usize,
+    color: Instruction,
+}
+
+#[derive(Debug, Clone, Copy, Format)]
+enum Heading {
+    #[format("U")]
+    Up,
+    #[format("R")]
+    Right,
+    #[format("D")]
+    Down,
+    #[format("L")]
+    Left,
+}
+
+/// The last digit of a colour
+#[derive(Debug, Clone, Copy, Format)]
+enum HexHeading {
+    #[format("0")]
+    Right,
+    #[format("1")]
+    Down,
+    #[format("2")]
+    Left,
+    #[format("3")]
+    Up,
 }
 
 impl Puzzle for Day18 {
@@ -27,114 +54,108 @@ impl Puzzle for Day18 {
     }
 
     fn part_one(&self, input: &str) -> super::PuzzleResult {
-        let input = input
-            .lines()
-            .map(|line| line.parse().unwrap())
-            .collect_vec();
+        let input = parse_input(input)?
+            .into_iter()
+            .map(|step| Instruction {
+                dir: step.dir.into(),
+                dist: step.dist,
+            })
+            .collect::<Vec<_>>();
 
-        solve_puzzle(&input)
+        fill_lagoon(&input)
     }
 
     fn part_two(&self, input: &str) -> super::PuzzleResult {
-        let input = input
-            .lines()
-            .map(|line| parse_input_hex(line).unwrap())
-            .collect_vec();
-
-        // for line in &input {
-        //     println!("{line:?}");
-        // }
+        let input = parse_input(input)?
+            .into_iter()
+            .map(|step| step.color)
+            .collect::<Vec<_>>();
 
-        solve_puzzle(&input)
+        // Far too big to dig out cell by cell
+        lagoon_area(&input)
     }
 }
 
-fn solve_puzzle(input: &[Instruction]) -> super::PuzzleResult {
-    let mut grid: Grid<Tile> = Grid::new(1, 1, Tile::Hole);
-    let mut pos = Pos::ZERO;
+fn parse_input(input: &str) -> Result<Vec<PlanStep>, ParseError> {
+    parse::parse_lines(input)
+}
+
+/// Dig out the trench and count the ground it encloses.
+///
+/// The top left corner of the trench turns right and down, so the cell
+/// diagonally inside it is inside the lagoon, as long as no two trenches run
+/// side by side.
+fn fill_lagoon(input: &[Instruction]) -> super::PuzzleResult {
+    let mut trench = SparseGrid::new();
+    let mut pos = Point::ZERO;
     for step in input {
-        pos = grid.make_space(&pos, step.dir, step.dist);
         for _ in 0..step.dist {
-            pos = match grid.step(&pos, step.dir) {
-                Some(p) => p,
-                None => {
-                    panic!(
-                        "attempted to step from {pos:?} {:?} Size: {:?}",
-                        step.dir,
-                        grid.size()
-                    );
-                }
-            };
-            grid.set(&pos, Tile::Hole);
+            pos += step.dir.into();
+            trench.set(&pos, ());
         }
-        println!("Building grid: {:?}", grid.size());
     }
 
-    println!("Done building grid: {:?}", grid.size());
-
-    // walk the path and flood fill to the right
-    let path = Path::from_grid(&grid, |g, p| matches!(g.value(p), Some(Tile::Hole))).unwrap();
-
-    println!("Done building path: {:?}", path.0.len());
-
-    for (pos, dir) in path.walk() {
-        let Some(right_pos) = grid.step(&pos, dir.turn_right()) else {
-            continue;
-        };
-        let cv = grid.value(&right_pos).copied();
-        if matches!(cv, Some(Tile::Ground)) {
-            grid.flood_fill(right_pos, Tile::Hole, |g, p| {
-                matches!(g.value(p), Some(Tile::Hole))
-            })
-        }
+    let Some(corner) = trench.cells().next() else {
+        anyhow::bail!("nothing was dug");
+    };
+    let inside = trench.region(corner + Point::new(1, 1), Connectivity::Four, |g, p| {
+        g.value(p).is_none()
+    });
+    if inside.touches_border {
+        anyhow::bail!("the trench doesn't enclose {:?}", inside.start);
     }
 
-    // println!("{:?}", grid);
-
-    let result = grid
-        .scan()
-        .filter(|p| matches!(grid.value(p), Some(Tile::Hole)))
-        .count();
-    Ok(result.to_string())
+    Ok((trench.len() + inside.area).to_string())
 }
 
-impl std::fmt::Debug for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Ground => f.write_str("."),
-            Self::Hole => f.write_str("#"),
-        }
+/// Area of the dug lagoon, trench included, without digging it.
+///
+/// The shoelace formula gives the area enclosed by the centres of the trench
+/// cells; Pick's theorem turns that into a count of the cells inside, and the
+/// trench itself adds its own length on top.
+fn lagoon_area(input: &[Instruction]) -> super::PuzzleResult {
+    let mut pos = Point::ZERO;
+    let mut area2 = 0;
+    let mut perimeter = 0;
+    for step in input {
+        let next = pos + Point::from(step.dir) * step.dist as i64;
+        area2 += pos.x * next.y - next.x * pos.y;
+        perimeter += step.dist as i64;
+        pos = next;
     }
-}
-
-fn parse_input_hex(input: &str) -> Result<Instruction, anyhow::Error> {
-    let parts = input.split(' ').collect_vec();
-    let hex = parts[2].replace('(', "").replace(')', "").replace('#', "");
-    let dist = usize::from_str_radix(&hex[0..5], 16).unwrap();
-    let dir = match u8::from_str_radix(&hex[5..], 16).unwrap() {
-        0 => Direction::East,
-        1 => Direction::South,
-        2 => Direction::West,
-        3 => Direction::North,
-        d => panic!("invalid direction {d}"),
-    };
 
-    Ok(Instruction { dir, dist })
+    let interior = area2.abs() / 2 - perimeter / 2 + 1;
+    Ok((interior + perimeter).to_string())
 }
 
-impl FromStr for Instruction {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(' ').collect_vec();
-        let dir = match parts[0] {
-            "U" => Direction::North,
-            "R" => Direction::East,
-            "D" => Direction::South,
-            "L" => Direction::West,
-            other => anyhow::bail!("Unexpected direction: {other}"),
+/// A colour, read as five hex digits of distance and then a heading
+impl Parse for Instruction {
+    fn parser(input: &str) -> PResult<'_, Self> {
+        let (input, dist) = parse::field(
+            "expected five hex digits",
+            nom::combinator::map_res(
+                nom::bytes::complete::take_while_m_n(5, 5, |c: char| c.is_ascii_hexdigit()),
+                |hex| usize::from_str_radix(hex, 16),
+            ),
+        )(input)?;
+        let (input, heading) = HexHeading::parser(input)?;
+        let dir = match heading {
+            HexHeading::Right => Direction::East,
+            HexHeading::Down => Direction::South,
+            HexHeading::Left => Direction::West,
+            HexHeading::Up => Direction::North,
         };
-        let dist = parts[1].parse().unwrap();
-        Ok(Instruction { dir, dist })
+        Ok((input, Instruction { dir, dist }))
+    }
+}
+
+impl From<Heading> for Direction {
+    fn from(value: Heading) -> Self {
+        match value {
+            Heading::Up => Direction::North,
+            Heading::Right => Direction::East,
+            Heading::Down => Direction::South,
+            Heading::Left => Direction::West,
+        }
     }
 }
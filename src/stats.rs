@@ -0,0 +1,285 @@
+use crate::history::{self, Entry, Outcome};
+use crate::leaderboard::{format_duration, unlock_ts};
+use crate::RootOpt;
+use clap::Parser;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug, Clone)]
+pub struct StatsCommand {
+    /// Write the markdown report to a file instead of stdout
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+}
+
+/// Per-day numbers, collected from the history file and the source tree
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DayStats {
+    pub wrong_submissions: usize,
+    /// When each part was first answered correctly
+    pub solved_at: [Option<i64>; 2],
+    /// Fastest recorded run of each part, in microseconds
+    pub fastest_run: [Option<u64>; 2],
+    pub lines_of_code: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub year: u16,
+    pub days: BTreeMap<u8, DayStats>,
+}
+
+impl Report {
+    pub fn new(year: u16, entries: &[Entry], lines_of_code: &BTreeMap<u8, usize>) -> Self {
+        let mut days: BTreeMap<u8, DayStats> = BTreeMap::new();
+
+        for entry in entries.iter().filter(|e| e.year() == year) {
+            match entry {
+                Entry::Run {
+                    day, part, micros, ..
+                } => {
+                    let stats = days.entry(*day).or_default();
+                    if let Some(fastest) = Self::part_slot(&mut stats.fastest_run, *part) {
+                        *fastest = Some(fastest.map_or(*micros, |f| f.min(*micros)));
+                    }
+                }
+                Entry::Submission {
+                    day,
+                    part,
+                    outcome,
+                    at,
+                    ..
+                } => {
+                    let stats = days.entry(*day).or_default();
+                    match outcome {
+                        Outcome::Incorrect => stats.wrong_submissions += 1,
+                        Outcome::Correct => {
+                            if let Some(solved) = Self::part_slot(&mut stats.solved_at, *part) {
+                                solved.get_or_insert(*at);
+                            }
+                        }
+                        Outcome::Wait | Outcome::WrongLevel => {}
+                    }
+                }
+            }
+        }
+
+        for (day, lines) in lines_of_code {
+            days.entry(*day).or_default().lines_of_code = Some(*lines);
+        }
+
+        Self { year, days }
+    }
+
+    fn part_slot<T>(slots: &mut [T], part: u8) -> Option<&mut T> {
+        slots.get_mut((part as usize).checked_sub(1)?)
+    }
+
+    pub fn to_markdown(&self) -> Result<String, anyhow::Error> {
+        let mut out = String::new();
+        writeln!(out, "# Advent of Code {}", self.year)?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "| Day | Part 1 solved | Part 2 solved | Wrong answers | Part 1 run | Part 2 run | Lines |"
+        )?;
+        writeln!(out, "|----:|--------------:|--------------:|--------------:|-----------:|-----------:|------:|")?;
+
+        for (day, stats) in &self.days {
+            let unlock = unlock_ts(self.year as i32, *day as u32)?;
+            let solved = |ts: Option<i64>| {
+                ts.map(|t| format_duration(t - unlock))
+                    .unwrap_or("-".to_string())
+            };
+            let run = |micros: Option<u64>| micros.map(format_micros).unwrap_or("-".to_string());
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                day,
+                solved(stats.solved_at[0]),
+                solved(stats.solved_at[1]),
+                stats.wrong_submissions,
+                run(stats.fastest_run[0]),
+                run(stats.fastest_run[1]),
+                stats
+                    .lines_of_code
+                    .map(|l| l.to_string())
+                    .unwrap_or("-".to_string()),
+            )?;
+        }
+
+        let slowest = self
+            .days
+            .iter()
+            .flat_map(|(day, stats)| {
+                (1..=2).filter_map(move |part| {
+                    stats.fastest_run[part as usize - 1].map(|micros| (*day, part, micros))
+                })
+            })
+            .sorted_by_key(|(_, _, micros)| std::cmp::Reverse(*micros))
+            .take(5)
+            .collect_vec();
+        if !slowest.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## Slowest solutions")?;
+            writeln!(out)?;
+            writeln!(out, "| Day | Part | Time |")?;
+            writeln!(out, "|----:|-----:|-----:|")?;
+            for (day, part, micros) in slowest {
+                writeln!(out, "| {day} | {part} | {} |", format_micros(micros))?;
+            }
+        }
+
+        let stars = self
+            .days
+            .values()
+            .map(|d| d.solved_at.iter().flatten().count())
+            .sum::<usize>();
+        let wrong = self
+            .days
+            .values()
+            .map(|d| d.wrong_submissions)
+            .sum::<usize>();
+        let lines = self
+            .days
+            .values()
+            .filter_map(|d| d.lines_of_code)
+            .sum::<usize>();
+        writeln!(out)?;
+        writeln!(
+            out,
+            "**Totals:** {stars} stars, {wrong} wrong answers, {lines} lines of code"
+        )?;
+
+        Ok(out)
+    }
+}
+
+fn format_micros(micros: u64) -> String {
+    match micros {
+        0..=999 => format!("{micros}µs"),
+        1_000..=999_999 => format!("{:.1}ms", micros as f64 / 1_000.0),
+        _ => format!("{:.2}s", micros as f64 / 1_000_000.0),
+    }
+}
+
+/// Non-blank, non-comment lines in each `day_XX.rs`
+fn lines_of_code(dir: &Path) -> Result<BTreeMap<u8, usize>, anyhow::Error> {
+    let mut lines = BTreeMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(day) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| {
+                n.strip_prefix("day_")?
+                    .strip_suffix(".rs")?
+                    .parse::<u8>()
+                    .ok()
+            })
+            .filter(|d| *d > 0)
+        else {
+            continue;
+        };
+        let count = std::fs::read_to_string(&path)?
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with("//"))
+            .count();
+        lines.insert(day, count);
+    }
+    Ok(lines)
+}
+
+impl StatsCommand {
+    pub fn run(&self, opt: &RootOpt) -> Result<(), anyhow::Error> {
+        log::info!("Running stats command");
+
+        let config = &opt.config;
+        let entries = history::load(&config.history_file)?;
+        let puzzle_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/puzzle");
+        let lines = lines_of_code(&puzzle_dir).unwrap_or_else(|e| {
+            log::warn!("couldn't count lines in {}: {e}", puzzle_dir.display());
+            Default::default()
+        });
+
+        let report = Report::new(config.year, &entries, &lines).to_markdown()?;
+        match &self.out {
+            Some(path) => std::fs::write(path, report)?,
+            None => print!("{report}"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        // Day 1 of 2023 unlocked at 1701406800
+        let entries = [
+            Entry::Submission {
+                year: 2023,
+                day: 1,
+                part: 1,
+                answer: "1".into(),
+                outcome: Outcome::Incorrect,
+                at: 1701407000,
+            },
+            Entry::Submission {
+                year: 2023,
+                day: 1,
+                part: 1,
+                answer: "2".into(),
+                outcome: Outcome::Correct,
+                at: 1701407400,
+            },
+            Entry::Run {
+                year: 2023,
+                day: 1,
+                part: 1,
+                answer: "2".into(),
+                micros: 2500,
+                at: 1701407390,
+            },
+            Entry::Run {
+                year: 2023,
+                day: 1,
+                part: 1,
+                answer: "2".into(),
+                micros: 1500,
+                at: 1701407395,
+            },
+            // Different year, ignored
+            Entry::Run {
+                year: 2022,
+                day: 1,
+                part: 2,
+                answer: "2".into(),
+                micros: 1,
+                at: 1701407395,
+            },
+        ];
+        let lines = BTreeMap::from([(1, 100), (2, 50)]);
+
+        let report = Report::new(2023, &entries, &lines);
+        assert_eq!(
+            report.days[&1],
+            DayStats {
+                wrong_submissions: 1,
+                solved_at: [Some(1701407400), None],
+                fastest_run: [Some(1500), None],
+                lines_of_code: Some(100),
+            }
+        );
+
+        let markdown = report.to_markdown().unwrap();
+        assert!(markdown.contains("| 1 | 00:10:00 | - | 1 | 1.5ms | - | 100 |"));
+        assert!(markdown.contains("| 2 | - | - | 0 | - | - | 50 |"));
+        assert!(markdown.contains("**Totals:** 1 stars, 1 wrong answers, 150 lines of code"));
+    }
+}
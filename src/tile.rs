@@ -0,0 +1,104 @@
+use crate::Pos;
+
+pub use macros::GridTile;
+
+/// A cell of a grid that's written as a single character.
+///
+/// Usually derived: `#[derive(GridTile)]` on a fieldless enum, with `#[tile('#')]`
+/// on each variant. `#[tile('L', render = '┖')]` renders a variant differently
+/// than it's parsed, and `#[tile(debug)]` on the enum makes `Debug` print the
+/// character too. The derive also implements `TryFrom<char>`, `FromStr` and
+/// `Display`.
+pub trait GridTile: Sized {
+    fn from_char(c: char) -> Option<Self>;
+
+    /// The character used to display the tile
+    fn to_char(&self) -> char;
+
+    /// Parse the tile at `pos`, so a bad character can be found in the input
+    fn parse_at(c: char, pos: Pos) -> Result<Self, TileError> {
+        Self::from_char(c).ok_or_else(|| TileError::new(c).at(pos))
+    }
+}
+
+/// A character that isn't any of a tile's variants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileError {
+    pub found: String,
+    pub pos: Option<Pos>,
+}
+
+impl TileError {
+    pub fn new(found: impl Into<String>) -> Self {
+        Self {
+            found: found.into(),
+            pos: None,
+        }
+    }
+
+    pub fn at(self, pos: Pos) -> Self {
+        Self {
+            pos: Some(pos),
+            ..self
+        }
+    }
+}
+
+impl std::fmt::Display for TileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unexpected tile {:?}", self.found)?;
+        if let Some(pos) = self.pos {
+            write!(f, " at line {}, column {}", pos.y + 1, pos.x + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TileError {}
+
+/// Parse every line of `input` into a row of tiles
+pub fn parse_tiles<T: GridTile>(input: &str) -> Result<Vec<Vec<T>>, TileError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(x, c)| T::parse_at(c, Pos { x, y }))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridTile;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, GridTile)]
+    enum Tile {
+        #[tile('.')]
+        Empty,
+        #[tile('#', render = '█')]
+        Wall,
+    }
+
+    #[test]
+    fn test_derive() {
+        assert_eq!(Tile::try_from('#'), Ok(Tile::Wall));
+        assert_eq!(".".parse::<Tile>(), Ok(Tile::Empty));
+        assert!("..".parse::<Tile>().is_err());
+        assert_eq!(Tile::Wall.to_string(), "█");
+        assert_eq!(Tile::Empty.to_char(), '.');
+    }
+
+    #[test]
+    fn test_parse_tiles() {
+        let rows: Vec<Vec<Tile>> = parse_tiles("..\n.#\n").unwrap();
+        assert_eq!(rows[1], [Tile::Empty, Tile::Wall]);
+
+        let err = parse_tiles::<Tile>("..\n.x\n").unwrap_err();
+        assert_eq!(err, TileError::new('x').at(Pos { x: 1, y: 1 }));
+        assert_eq!(err.to_string(), "unexpected tile \"x\" at line 2, column 2");
+    }
+}